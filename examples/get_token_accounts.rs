@@ -16,7 +16,8 @@ async fn main() {
 
     let client = solana_mirror::SolanaMirror::new(watch, rpc);
 
-    let token_accounts: (Vec<ParsedAta>, Option<Vec<ParsedPosition>>) = client.get_token_accounts(Some(true)).await.unwrap();
+    let token_accounts: (Vec<ParsedAta>, Option<Vec<ParsedPosition>>) =
+        client.get_token_accounts(Some(true)).await.unwrap();
 
     println!("{:?}", token_accounts);
 }
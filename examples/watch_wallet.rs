@@ -0,0 +1,29 @@
+use dotenv::dotenv;
+use futures::StreamExt;
+use solana_mirror::{self, SolanaMirrorPubsubClient};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use tokio::main;
+
+#[main]
+async fn main() {
+    dotenv().ok();
+
+    let test_address =
+        std::env::var("TEST_ADDRESS").expect("TEST_ADDRESS environment variable not set");
+
+    let watch = Pubkey::from_str(&test_address).expect("Invalid public key format");
+    let ws = std::env::var("WS").expect("WS environment variable not set");
+
+    let pubsub = SolanaMirrorPubsubClient::new(ws);
+
+    let mut account_updates = pubsub.account_subscribe(&watch, None).unwrap();
+    let mut logs = pubsub.logs_subscribe(&watch, None).unwrap();
+
+    loop {
+        tokio::select! {
+            Some(update) = account_updates.next() => println!("{:?}", update),
+            Some(log) = logs.next() => println!("{:?}", log),
+        }
+    }
+}
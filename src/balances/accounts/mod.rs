@@ -1,35 +1,74 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
-use futures::future::join_all;
+use futures::future::{join, join_all};
 use solana_program::native_token::LAMPORTS_PER_SOL;
 use solana_sdk::pubkey::Pubkey;
 use spl_token::id as spl_token_id;
 
 use crate::{
+    balances::{
+        accounts::types::ParsedMetadata,
+        mints::{amount_to_ui_amount, fetch_mints, with_account_extensions, MintInfo},
+    },
     client::{
         types::AccountData, GetTokenAccountsByOwnerConfig, GetTokenAccountsByOwnerFilter,
         SolanaMirrorRpcClient,
     },
-    consts::{SOL_ADDRESS, SOL_IMAGE},
+    consts::{SOL_ADDRESS, SOL_IMAGE, SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID},
     enums::Error,
-    price::get_price,
+    metadata::{MetadataResolver, MetadataSource},
+    price::{get_price, get_quote, LstOracle},
     types::FormattedAmount,
-    utils::{fetch_image, fetch_metadata},
 };
 
+use types::ParsedAccounts;
 pub use types::ParsedAta;
 
 pub mod types;
 
 /// Fetches the token accounts associated with the given address and parses them.
+/// Liquid staking tokens known to `lst` are valued from their stake pool
 pub async fn get_parsed_accounts(
     client: &SolanaMirrorRpcClient,
+    resolver: &MetadataResolver,
+    lst: &LstOracle,
     address: &Pubkey,
 ) -> Result<Vec<ParsedAta>, Error> {
+    let parsed = get_parsed_accounts_with_mints(client, resolver, lst, address).await?;
+    Ok(parsed.accounts)
+}
+
+/// Like [`get_parsed_accounts`], also handing back the mints and their metadata that
+/// were fetched on the way
+pub(crate) async fn get_parsed_accounts_with_mints(
+    client: &SolanaMirrorRpcClient,
+    resolver: &MetadataResolver,
+    lst: &LstOracle,
+    address: &Pubkey,
+) -> Result<ParsedAccounts, Error> {
     let accounts = get_accounts(client, address).await?;
-    let parse_futures = accounts
+
+    let mints: Vec<String> = accounts
         .iter()
-        .map(|account| parse_account(client, account));
+        .map(|account| account.account.data.parsed.info.mint.clone())
+        .collect();
+    let mint_infos = fetch_mints(client, &mints).await;
+    let (metadata, lst_prices) = join(
+        resolver.resolve(client, &mints, &mint_infos),
+        lst.get_prices(client, &mints),
+    )
+    .await;
+
+    let parse_futures = accounts.iter().map(|account| {
+        parse_account(
+            client,
+            resolver,
+            account,
+            &metadata,
+            &mint_infos,
+            &lst_prices,
+        )
+    });
 
     let parsed_results = join_all(parse_futures).await;
 
@@ -42,7 +81,11 @@ pub async fn get_parsed_accounts(
     }
 
     parsed_accounts.push(get_solana(client, address).await);
-    Ok(parsed_accounts)
+    Ok(ParsedAccounts {
+        accounts: parsed_accounts,
+        mint_infos,
+        metadata,
+    })
 }
 
 /// Fetches the SOL account associated with the given address.
@@ -59,25 +102,52 @@ async fn get_solana(client: &SolanaMirrorRpcClient, pubkey: &Pubkey) -> ParsedAt
         decimals: 9,
         name: "Solana".to_string(),
         symbol: "SOL".to_string(),
+        verified: true,
+        tags: Vec::new(),
+        metadata_source: MetadataSource::None,
         image: SOL_IMAGE.to_string(),
         price,
+        no_route: false,
         balance: FormattedAmount {
             amount: amount.to_string(),
             formatted,
         },
+        token_program: SYSTEM_PROGRAM_ID.to_string(),
+        extensions: None,
+        spam: None,
+        risk_profile: None,
     }
 }
 
-/// Fetches the token accounts associated with the given address.
-async fn get_accounts(
+/// Fetches the token accounts associated with the given address,
+/// from both the SPL Token and Token-2022 programs.
+pub(crate) async fn get_accounts(
+    client: &SolanaMirrorRpcClient,
+    pubkey: &Pubkey,
+) -> Result<Vec<AccountData>, Error> {
+    let (spl_accounts, token_2022_accounts) = join(
+        get_accounts_by_program(client, pubkey, &spl_token_id().to_string()),
+        get_accounts_by_program(client, pubkey, TOKEN_2022_PROGRAM_ID),
+    )
+    .await;
+
+    let mut accounts = spl_accounts?;
+    accounts.extend(token_2022_accounts?);
+
+    Ok(accounts)
+}
+
+/// Fetches the token accounts of the given address owned by a single token program.
+async fn get_accounts_by_program(
     client: &SolanaMirrorRpcClient,
     pubkey: &Pubkey,
+    program_id: &str,
 ) -> Result<Vec<AccountData>, Error> {
     let accounts = client
         .get_token_accounts_by_owner(
             pubkey,
             Some(GetTokenAccountsByOwnerFilter {
-                program_id: spl_token_id().to_string(),
+                program_id: program_id.to_string(),
             }),
             Some(GetTokenAccountsByOwnerConfig {
                 commitment: None,
@@ -94,23 +164,46 @@ async fn get_accounts(
 /// Parses the given account.
 async fn parse_account(
     client: &SolanaMirrorRpcClient,
+    resolver: &MetadataResolver,
     account: &AccountData,
+    metadata: &HashMap<String, ParsedMetadata>,
+    mint_infos: &HashMap<String, MintInfo>,
+    lst_prices: &HashMap<String, f64>,
 ) -> Result<ParsedAta, Error> {
     let data = &account.account.data;
     let info = &data.parsed.info;
     let mint = &info.mint;
 
-    let metadata = fetch_metadata(client, mint).await;
+    let metadata = metadata.get(mint).cloned().unwrap_or_default();
+
+    let listed = resolver.token_list_entry(mint);
 
     let ata = &account.pubkey;
+    // The mint is the source of truth, token lists can carry stale decimals
     let decimals = info.token_amount.decimals;
     let amount = info.token_amount.amount.parse::<u64>().unwrap();
-    let formatted = info.token_amount.ui_amount;
 
-    let mint_pubkey = Pubkey::from_str(mint).unwrap();
-    let price = get_price(client, mint_pubkey, Some(decimals)).await;
+    let extensions = mint_infos
+        .get(mint)
+        .and_then(|mint_info| mint_info.extensions.clone())
+        .map(|extensions| {
+            with_account_extensions(
+                extensions,
+                info.extensions.as_deref().unwrap_or_default(),
+                decimals,
+            )
+        });
+    let formatted = amount_to_ui_amount(amount, decimals, extensions.as_ref());
 
-    let image = fetch_image(&metadata).await;
+    let (price, no_route) = match lst_prices.get(mint) {
+        Some(price) => (Some(*price), false),
+        None => match get_quote(client, Pubkey::from_str(mint).unwrap(), Some(decimals)).await {
+            Ok(price) => (price, price.is_none()),
+            Err(_) => (None, false),
+        },
+    };
+
+    let image = resolver.fetch_image(client, mint, &metadata).await;
 
     Ok(ParsedAta {
         mint: mint.to_string(),
@@ -118,11 +211,156 @@ async fn parse_account(
         decimals,
         name: metadata.name,
         symbol: metadata.symbol,
+        verified: listed.is_some(),
+        tags: listed.map(|listed| listed.tags.clone()).unwrap_or_default(),
+        metadata_source: metadata.source,
         image,
         price,
+        no_route,
         balance: FormattedAmount {
             amount: amount.to_string(),
             formatted,
         },
+        token_program: account.account.owner.clone(),
+        extensions,
+        spam: None,
+        risk_profile: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{client::transport::MemoryTransport, consts::USDC_ADDRESS};
+
+    const OWNER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const USDC_ATA: &str = "3emsAVdmGKERbHjmGfQ6oZ1e35dkf5iYcS6U4CPKFVaa";
+
+    fn context() -> Value {
+        json!({ "apiVersion": "2.0.0", "slot": 1 })
+    }
+
+    fn token_accounts_params(program_id: &str) -> Value {
+        json!([
+            OWNER,
+            { "programId": program_id },
+            { "commitment": null, "minContextSlot": null, "dataSlice": null, "encoding": "jsonParsed" }
+        ])
+    }
+
+    fn usdc_account() -> Value {
+        json!({
+            "pubkey": USDC_ATA,
+            "account": {
+                "data": {
+                    "parsed": {
+                        "info": {
+                            "isNative": false,
+                            "mint": USDC_ADDRESS,
+                            "owner": OWNER,
+                            "state": "initialized",
+                            "tokenAmount": {
+                                "amount": "12500000",
+                                "decimals": 6,
+                                "uiAmount": 12.5,
+                                "uiAmountString": "12.5"
+                            }
+                        },
+                        "type": "account"
+                    },
+                    "program": "spl-token",
+                    "space": 165
+                },
+                "executable": false,
+                "lamports": 2039280,
+                "owner": spl_token_id().to_string(),
+                "rentEpoch": 0,
+                "space": 165
+            }
+        })
+    }
+
+    fn usdc_mint() -> Value {
+        json!({
+            "data": {
+                "parsed": {
+                    "info": {
+                        "decimals": 6,
+                        "freezeAuthority": null,
+                        "isInitialized": true,
+                        "mintAuthority": null,
+                        "supply": "1000000000000"
+                    },
+                    "type": "mint"
+                },
+                "program": "spl-token",
+                "space": 82
+            },
+            "executable": false,
+            "lamports": 1461600,
+            "owner": spl_token_id().to_string(),
+            "rentEpoch": 0,
+            "space": 82
+        })
+    }
+
+    #[tokio::test]
+    async fn parses_token_accounts_from_canned_responses() {
+        let transport = MemoryTransport::new()
+            .with_response(
+                "getTokenAccountsByOwner",
+                token_accounts_params(&spl_token_id().to_string()),
+                json!({ "context": context(), "value": [usdc_account()] }),
+            )
+            .with_response(
+                "getTokenAccountsByOwner",
+                token_accounts_params(TOKEN_2022_PROGRAM_ID),
+                json!({ "context": context(), "value": [] }),
+            )
+            .with_response(
+                "getMultipleAccounts",
+                json!([
+                    [USDC_ADDRESS],
+                    { "commitment": null, "encoding": "jsonParsed", "dataSlice": null, "minContextSlot": null }
+                ]),
+                json!({ "context": context(), "value": [usdc_mint()] }),
+            )
+            .with_response(
+                "getBalance",
+                json!([OWNER, null]),
+                json!({ "context": context(), "value": 1_500_000_000u64 }),
+            );
+        let client = SolanaMirrorRpcClient::with_transport(Arc::new(transport));
+
+        let accounts = get_parsed_accounts(
+            &client,
+            &MetadataResolver::default(),
+            &LstOracle::default(),
+            &Pubkey::from_str(OWNER).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(accounts.len(), 2);
+
+        let usdc = &accounts[0];
+        assert_eq!(usdc.mint, USDC_ADDRESS);
+        assert_eq!(usdc.ata, USDC_ATA);
+        assert_eq!(usdc.decimals, 6);
+        assert_eq!(usdc.balance.amount, "12500000");
+        assert_eq!(usdc.balance.formatted, 12.5);
+        assert_eq!(usdc.price, Some(1.0));
+        assert_eq!(usdc.token_program, spl_token_id().to_string());
+
+        let sol = &accounts[1];
+        assert_eq!(sol.mint, SOL_ADDRESS);
+        assert_eq!(sol.balance.amount, "1500000000");
+        assert_eq!(sol.balance.formatted, 1.5);
+        // No Jupiter quote was canned, so SOL is left unpriced
+        assert_eq!(sol.price, None);
+    }
+}
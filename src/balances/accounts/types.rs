@@ -1,6 +1,16 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::types::FormattedAmount;
+use crate::{
+    balances::{
+        mints::{MintInfo, TokenExtensions},
+        risk::MintRiskProfile,
+        spam::SpamVerdict,
+    },
+    metadata::MetadataSource,
+    types::FormattedAmount,
+};
 
 #[derive(Default, Debug, Serialize)]
 pub struct ParsedAta {
@@ -9,9 +19,39 @@ pub struct ParsedAta {
     pub decimals: u8,
     pub name: String,
     pub symbol: String,
+    /// Whether the token is listed in the token registry
+    pub verified: bool,
+    /// Tags of the token in the token registry
+    pub tags: Vec<String>,
+    #[serde(rename = "metadataSource")]
+    pub metadata_source: MetadataSource,
     pub image: String,
     pub price: Option<f64>,
+    /// Whether Jupiter answered that the token has no route to USDC. False when the
+    /// quote couldn't be fetched or the price came from elsewhere
+    #[serde(rename = "noRoute")]
+    pub no_route: bool,
     pub balance: FormattedAmount,
+    /// Program that owns the account, SPL Token or Token-2022. The System program for SOL
+    #[serde(rename = "tokenProgram")]
+    pub token_program: String,
+    /// Token-2022 extensions of the mint and the account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<TokenExtensions>,
+    /// Spam classification, only set when the spam policy flags or hides holdings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spam: Option<SpamVerdict>,
+    /// Authorities, supply and holder concentration of the mint, only set when requested
+    #[serde(rename = "riskProfile", skip_serializing_if = "Option::is_none")]
+    pub risk_profile: Option<MintRiskProfile>,
+}
+
+/// Parsed token accounts, with the mints and metadata fetched on the way so later
+/// steps don't fetch them again
+pub(crate) struct ParsedAccounts {
+    pub accounts: Vec<ParsedAta>,
+    pub mint_infos: HashMap<String, MintInfo>,
+    pub metadata: HashMap<String, ParsedMetadata>,
 }
 
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
@@ -19,9 +59,9 @@ pub struct ParsedMetadata {
     pub name: String,
     pub symbol: String,
     pub uri: String,
-}
-
-#[derive(Deserialize)]
-pub struct ImageResponse {
-    pub image: String,
+    #[serde(default)]
+    pub source: MetadataSource,
+    /// Whether the metadata can still be changed, when the source tells
+    #[serde(default)]
+    pub mutable: Option<bool>,
 }
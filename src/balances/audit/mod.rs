@@ -0,0 +1,153 @@
+use std::{str::FromStr, sync::OnceLock};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    balances::accounts::get_accounts,
+    client::{
+        types::{AccountData, TokenAmount},
+        SolanaMirrorRpcClient,
+    },
+    enums::Error,
+    types::FormattedAmount,
+};
+
+pub use types::{DelegateApproval, FrozenAccount, TokenAudit};
+
+pub mod types;
+
+const JUPITER_V6_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+const MAGIC_EDEN_V2_PROGRAM_ID: &str = "M2mx93ekt1fmXSVkTrUL9xVFHkmME8HTUi5Cyc5aF7K";
+/// Jupiter v6 signs with one of its program authorities, seeded with their id
+const JUPITER_AUTHORITY_IDS: u8 = 8;
+
+/// Programs delegates are commonly approved to, by address. Most approvals go to a PDA
+/// of the program rather than the program itself, see [`known_delegate_pdas`]
+const KNOWN_PROGRAMS: &[(&str, &str)] = &[
+    (JUPITER_V6_PROGRAM_ID, "Jupiter Aggregator v6"),
+    (
+        "jupoNjAxXgZ4rjzxzPMP4oxduvQsQtZzyknqvzYNrNu",
+        "Jupiter Limit Order",
+    ),
+    (
+        "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8",
+        "Raydium AMM",
+    ),
+    (
+        "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK",
+        "Raydium Concentrated Liquidity",
+    ),
+    (
+        "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc",
+        "Orca Whirlpools",
+    ),
+    (MAGIC_EDEN_V2_PROGRAM_ID, "Magic Eden v2"),
+    ("TSWAPaqyCSx2KABk68Shruf4rp7CxcNi8hAsbdwmHbN", "Tensor Swap"),
+    (
+        "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s",
+        "Metaplex Token Metadata",
+    ),
+];
+
+/// Audits the token accounts of the given address: active delegate approvals and
+/// frozen accounts
+pub async fn get_token_audit(
+    client: &SolanaMirrorRpcClient,
+    address: &Pubkey,
+) -> Result<TokenAudit, Error> {
+    let accounts = get_accounts(client, address).await?;
+
+    let mut delegates: Vec<DelegateApproval> = accounts
+        .iter()
+        .filter_map(parse_delegate_approval)
+        .collect();
+    delegates.sort_by(|a, b| share(b).total_cmp(&share(a)));
+
+    let frozen: Vec<FrozenAccount> = accounts
+        .iter()
+        .filter(|account| account.account.data.parsed.info.state == "frozen")
+        .map(|account| FrozenAccount {
+            address: account.pubkey.clone(),
+            mint: account.account.data.parsed.info.mint.clone(),
+            balance: to_formatted_amount(&account.account.data.parsed.info.token_amount),
+        })
+        .collect();
+
+    Ok(TokenAudit { delegates, frozen })
+}
+
+/// Returns the name of the program at the address, or of the program signing with the
+/// PDA at the address, if it is a known one. Other PDAs and keypairs get no label
+pub fn known_program_label(address: &str) -> Option<&'static str> {
+    KNOWN_PROGRAMS
+        .iter()
+        .copied()
+        .chain(
+            known_delegate_pdas()
+                .iter()
+                .map(|(pda, label)| (pda.as_str(), *label)),
+        )
+        .find(|(program, _)| *program == address)
+        .map(|(_, label)| label)
+}
+
+/// Authority PDAs known programs take approvals with, derived once
+fn known_delegate_pdas() -> &'static [(String, &'static str)] {
+    static PDAS: OnceLock<Vec<(String, &'static str)>> = OnceLock::new();
+
+    PDAS.get_or_init(|| {
+        let jupiter = Pubkey::from_str(JUPITER_V6_PROGRAM_ID).unwrap();
+        let magic_eden = Pubkey::from_str(MAGIC_EDEN_V2_PROGRAM_ID).unwrap();
+
+        let mut pdas: Vec<(String, &'static str)> = (0..JUPITER_AUTHORITY_IDS)
+            .map(|id| {
+                let (pda, _) = Pubkey::find_program_address(&[b"authority", &[id]], &jupiter);
+                (pda.to_string(), "Jupiter Aggregator v6")
+            })
+            .collect();
+
+        let (pda, _) = Pubkey::find_program_address(&[b"m2", b"signer"], &magic_eden);
+        pdas.push((pda.to_string(), "Magic Eden v2"));
+
+        pdas
+    })
+}
+
+/// Parses the approval of the account. None when there is no delegate or nothing left to spend
+fn parse_delegate_approval(account: &AccountData) -> Option<DelegateApproval> {
+    let info = &account.account.data.parsed.info;
+    let delegate = info.delegate.as_ref()?;
+    let delegated_amount = info.delegated_amount.as_ref()?;
+
+    let approved = delegated_amount.amount.parse::<u64>().ok()?;
+    if approved == 0 {
+        return None;
+    }
+    let balance = info.token_amount.amount.parse::<u64>().unwrap_or_default();
+
+    Some(DelegateApproval {
+        address: account.pubkey.clone(),
+        mint: info.mint.clone(),
+        delegate: delegate.clone(),
+        delegate_label: known_program_label(delegate).map(String::from),
+        delegated_amount: to_formatted_amount(delegated_amount),
+        balance: to_formatted_amount(&info.token_amount),
+        covers_balance: approved >= balance,
+    })
+}
+
+/// Part of the balance the delegate can take, 1 when it covers all of it
+fn share(approval: &DelegateApproval) -> f64 {
+    if approval.covers_balance || approval.balance.formatted == 0.0 {
+        1.0
+    } else {
+        approval.delegated_amount.formatted / approval.balance.formatted
+    }
+}
+
+fn to_formatted_amount(token_amount: &TokenAmount) -> FormattedAmount {
+    FormattedAmount {
+        amount: token_amount.amount.clone(),
+        formatted: token_amount.ui_amount,
+    }
+}
@@ -0,0 +1,38 @@
+use serde::Serialize;
+
+use crate::types::FormattedAmount;
+
+/// Token account someone other than the owner can spend from
+#[derive(Debug, Clone, Serialize)]
+pub struct DelegateApproval {
+    /// The token account
+    pub address: String,
+    pub mint: String,
+    pub delegate: String,
+    /// Name of the delegate when it is a known program or one of its authority PDAs
+    #[serde(rename = "delegateLabel")]
+    pub delegate_label: Option<String>,
+    /// Amount the delegate can still transfer
+    #[serde(rename = "delegatedAmount")]
+    pub delegated_amount: FormattedAmount,
+    pub balance: FormattedAmount,
+    /// Whether the delegate can take the whole balance
+    #[serde(rename = "coversBalance")]
+    pub covers_balance: bool,
+}
+
+/// Token account the freeze authority of its mint has frozen
+#[derive(Debug, Clone, Serialize)]
+pub struct FrozenAccount {
+    /// The token account
+    pub address: String,
+    pub mint: String,
+    pub balance: FormattedAmount,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenAudit {
+    /// Active approvals, the largest share of the balance first
+    pub delegates: Vec<DelegateApproval>,
+    pub frozen: Vec<FrozenAccount>,
+}
@@ -1,18 +1,19 @@
 use futures::future::join_all;
 use serde::de::DeserializeOwned;
 use solana_sdk::pubkey::Pubkey;
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
 use crate::{
     balances::{
-        accounts::get_parsed_accounts,
+        accounts::{get_parsed_accounts, types::ParsedMetadata},
         dapps::types::{ProtocolInfo, TokenPosition},
     },
-    client::{GetAccountDataConfig, SolanaMirrorRpcClient},
+    client::{GetMultipleAccountsConfig, SolanaMirrorRpcClient},
     enums::Error,
-    price::get_price,
+    metadata::MetadataResolver,
+    price::{get_price, LstOracle},
     types::{FormattedAmount, FormattedAmountWithPrice},
-    utils::{calculate_concentrated_liquidity_amounts, fetch_image, fetch_metadata},
+    utils::calculate_concentrated_liquidity_amounts,
 };
 
 pub use super::types::ParsedPosition;
@@ -24,19 +25,48 @@ const RAYDIUM_CL_PROGRAM_ID: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWq
 
 pub async fn get_raydium_positions(
     client: &SolanaMirrorRpcClient,
+    resolver: &MetadataResolver,
+    lst: &LstOracle,
     address: &Pubkey,
 ) -> Result<Vec<ParsedPosition>, Error> {
-    let parsed_accounts = get_parsed_accounts(&client, &address).await?;
+    let parsed_accounts = get_parsed_accounts(client, resolver, lst, address).await?;
 
     let position_mints: Vec<&str> = parsed_accounts
         .iter()
-        .filter(|account| account.balance.amount == "1")
+        .filter(|account| account.balance.amount == "1" && account.decimals == 0)
         .map(|account| account.mint.as_str())
         .collect();
 
-    let parse_raydium_position_futures: Vec<_> = position_mints
+    let positions = get_positions(client, &position_mints).await?;
+    if positions.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let pools = get_pools(client, &positions).await?;
+
+    let mut mints: Vec<String> = Vec::new();
+    for (mint_protocol, position) in &positions {
+        mints.push(mint_protocol.to_string());
+        if let Some(pool) = pools.get(&position.pool_id) {
+            mints.push(pool.mint_a.to_string());
+            mints.push(pool.mint_b.to_string());
+        }
+    }
+    let metadata = resolver.resolve(client, &mints, &HashMap::new()).await;
+
+    let parse_raydium_position_futures: Vec<_> = positions
         .iter()
-        .map(|&mint| get_raydium_position_by_mint(&client, mint))
+        .filter_map(|(mint_protocol, position)| {
+            let pool = pools.get(&position.pool_id)?;
+            Some(parse_raydium_position(
+                client,
+                resolver,
+                mint_protocol,
+                position,
+                pool,
+                &metadata,
+            ))
+        })
         .collect();
 
     let parsed_raydium_results: Vec<Result<ParsedPosition, Error>> =
@@ -53,14 +83,14 @@ pub async fn get_raydium_positions(
     Ok(parsed_raydium_positions)
 }
 
-async fn get_raydium_position_by_mint(
+async fn parse_raydium_position(
     client: &SolanaMirrorRpcClient,
+    resolver: &MetadataResolver,
     mint_protocol: &str,
+    position: &Position,
+    pool: &Pool,
+    metadata: &HashMap<String, ParsedMetadata>,
 ) -> Result<ParsedPosition, Error> {
-    let position_address = get_position_address(mint_protocol).unwrap();
-    let position = get_position_data(client, &position_address).await?;
-    let pool = get_pool_data(client, &position.pool_id).await?;
-
     let (amount_a, amount_b) = calculate_concentrated_liquidity_amounts(
         position.liquidity,
         position.tick_lower,
@@ -68,16 +98,28 @@ async fn get_raydium_position_by_mint(
         pool.sqrt_price_x64,
     );
 
-    let metadata_protocol = fetch_metadata(client, mint_protocol).await;
-    let image_protocol = fetch_image(&metadata_protocol).await;
-
     let mint_a = pool.mint_a;
     let mint_b = pool.mint_b;
 
-    let metadata_token_a = fetch_metadata(client, &mint_a.to_string()).await;
-    let metadata_token_b = fetch_metadata(client, &mint_b.to_string()).await;
-    let image_a = fetch_image(&metadata_token_a).await;
-    let image_b = fetch_image(&metadata_token_b).await;
+    let metadata_protocol = metadata.get(mint_protocol).cloned().unwrap_or_default();
+    let metadata_token_a = metadata
+        .get(&mint_a.to_string())
+        .cloned()
+        .unwrap_or_default();
+    let metadata_token_b = metadata
+        .get(&mint_b.to_string())
+        .cloned()
+        .unwrap_or_default();
+
+    let image_protocol = resolver
+        .fetch_image(client, mint_protocol, &metadata_protocol)
+        .await;
+    let image_a = resolver
+        .fetch_image(client, &mint_a.to_string(), &metadata_token_a)
+        .await;
+    let image_b = resolver
+        .fetch_image(client, &mint_b.to_string(), &metadata_token_b)
+        .await;
 
     let decimals_a = pool.mint_decimals_a;
     let decimals_b = pool.mint_decimals_b;
@@ -146,37 +188,69 @@ fn get_position_address(nft_mint: &str) -> Result<Pubkey, Error> {
     Ok(position_address)
 }
 
-async fn get_position_data(
+/// Loads the position accounts of every candidate NFT in one go.
+/// NFTs without a Raydium CLMM position are skipped
+async fn get_positions<'a>(
     client: &SolanaMirrorRpcClient,
-    position_address: &Pubkey,
-) -> Result<Position, Error> {
-    let encoded_position = match client
-        .get_account_info(
-            position_address,
-            Some(GetAccountDataConfig {
-                commitment: None,
-                encoding: Some("jsonParsed".to_string()),
-            }),
-        )
-        .await
-    {
-        Ok(encoded_position) => encoded_position,
-        Err(_) => return Ok(Position::default()),
-    };
-    decode_data(&encoded_position)
+    position_mints: &[&'a str],
+) -> Result<Vec<(&'a str, Position)>, Error> {
+    let position_addresses = position_mints
+        .iter()
+        .map(|mint| get_position_address(mint))
+        .collect::<Result<Vec<Pubkey>, Error>>()?;
+
+    let accounts = client
+        .get_multiple_accounts(&position_addresses, Some(base64_config()))
+        .await?;
+
+    let mut positions = Vec::new();
+    for (&mint, account) in position_mints.iter().zip(accounts) {
+        let account = match account {
+            Some(account) if account.owner == RAYDIUM_CL_PROGRAM_ID => account,
+            _ => continue,
+        };
+
+        if let Some(data) = account.decode_data() {
+            positions.push((mint, decode_data::<Position>(&data)?));
+        }
+    }
+
+    Ok(positions)
 }
 
-async fn get_pool_data(client: &SolanaMirrorRpcClient, pool_id: &Pubkey) -> Result<Pool, Error> {
-    let encoded_pool = client
-        .get_account_info(
-            pool_id,
-            Some(GetAccountDataConfig {
-                commitment: None,
-                encoding: Some("jsonParsed".to_string()),
-            }),
-        )
+/// Loads the pools referenced by the positions, each one once
+async fn get_pools(
+    client: &SolanaMirrorRpcClient,
+    positions: &[(&str, Position)],
+) -> Result<HashMap<Pubkey, Pool>, Error> {
+    let mut pool_ids: Vec<Pubkey> = Vec::new();
+    for (_, position) in positions {
+        if !pool_ids.contains(&position.pool_id) {
+            pool_ids.push(position.pool_id);
+        }
+    }
+
+    let accounts = client
+        .get_multiple_accounts(&pool_ids, Some(base64_config()))
         .await?;
-    decode_data(&encoded_pool)
+
+    let mut pools = HashMap::new();
+    for (pool_id, account) in pool_ids.into_iter().zip(accounts) {
+        if let Some(data) = account.and_then(|account| account.decode_data()) {
+            pools.insert(pool_id, decode_data::<Pool>(&data)?);
+        }
+    }
+
+    Ok(pools)
+}
+
+fn base64_config() -> GetMultipleAccountsConfig {
+    GetMultipleAccountsConfig {
+        commitment: None,
+        encoding: Some("base64".to_string()),
+        data_slice: None,
+        min_context_slot: None,
+    }
 }
 
 fn decode_data<T: DeserializeOwned>(data: &[u8]) -> Result<T, Error> {
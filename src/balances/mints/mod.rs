@@ -0,0 +1,327 @@
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::future::join_all;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    client::{
+        types::{TokenLargestAccount, UiAccount, UiAccountData},
+        GetMultipleAccountsConfig, SolanaMirrorRpcClient,
+    },
+    types::FormattedAmount,
+    utils::clean_string,
+};
+
+pub use types::{
+    ExtensionRisk, InterestBearing, MetadataPointer, MintInfo, TokenExtensions, TokenMetadata,
+    TransferFee,
+};
+use types::{
+    UiDefaultAccountState, UiInterestBearingConfig, UiMetadataPointer, UiPermanentDelegate,
+    UiTransferFeeAmount, UiTransferFeeConfig, UiTransferHook,
+};
+
+pub mod types;
+
+/// Seconds per year used by Token-2022 to accrue interest (365.24 days)
+const SECONDS_PER_YEAR: f64 = 31_556_736.0;
+
+/// Fetches the given mint accounts with `getMultipleAccounts` and decodes them.
+/// Mints that don't exist or can't be parsed are left out
+pub async fn fetch_mints(
+    client: &SolanaMirrorRpcClient,
+    mints: &[String],
+) -> HashMap<String, MintInfo> {
+    let mut unique_mints: Vec<String> = Vec::new();
+    for mint in mints {
+        if !unique_mints.contains(mint) && Pubkey::from_str(mint).is_ok() {
+            unique_mints.push(mint.to_string());
+        }
+    }
+
+    let pubkeys: Vec<Pubkey> = unique_mints
+        .iter()
+        .map(|mint| Pubkey::from_str(mint).unwrap())
+        .collect();
+
+    let accounts = match client
+        .get_multiple_accounts(
+            &pubkeys,
+            Some(GetMultipleAccountsConfig {
+                commitment: None,
+                encoding: Some("jsonParsed".to_string()),
+                data_slice: None,
+                min_context_slot: None,
+            }),
+        )
+        .await
+    {
+        Ok(accounts) => accounts,
+        Err(_) => return HashMap::new(),
+    };
+
+    // Transfer fee changes take effect at a given epoch, which is only fetched when needed
+    let epoch = if accounts.iter().flatten().any(has_transfer_fee) {
+        client
+            .get_epoch_info(None)
+            .await
+            .ok()
+            .map(|info| info.epoch)
+    } else {
+        None
+    };
+
+    unique_mints
+        .into_iter()
+        .zip(accounts)
+        .filter_map(|(mint, account)| {
+            let mint_info = parse_mint_account(&mint, &account?, epoch)?;
+            Some((mint, mint_info))
+        })
+        .collect()
+}
+
+/// Fetches the largest accounts of each mint with `getTokenLargestAccounts`, one request
+/// per mint. Mints whose request fails are left out
+pub async fn fetch_largest_accounts(
+    client: &SolanaMirrorRpcClient,
+    mints: &[String],
+) -> HashMap<String, Vec<TokenLargestAccount>> {
+    let largest_futures = mints.iter().map(|mint| async move {
+        let mint_pubkey = Pubkey::from_str(mint).ok()?;
+        let largest_accounts = client
+            .get_token_largest_accounts(&mint_pubkey, None)
+            .await
+            .ok()?;
+
+        Some((mint.to_string(), largest_accounts.result.value))
+    });
+
+    join_all(largest_futures)
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Decodes a mint account fetched with jsonParsed. None for any other account
+pub(crate) fn parse_mint_account(
+    mint: &str,
+    account: &UiAccount,
+    epoch: Option<u64>,
+) -> Option<MintInfo> {
+    match &account.data {
+        UiAccountData::Json(data) if data.parsed["type"] == "mint" => {
+            parse_mint(mint, &account.owner, &data.parsed["info"], epoch)
+        }
+        _ => None,
+    }
+}
+
+/// Whether the jsonParsed mint has a transfer fee config
+fn has_transfer_fee(account: &UiAccount) -> bool {
+    let extensions = match &account.data {
+        UiAccountData::Json(data) => data.parsed["info"]["extensions"].as_array(),
+        UiAccountData::Binary(_, _) => None,
+    };
+
+    extensions.is_some_and(|extensions| {
+        extensions
+            .iter()
+            .any(|extension| extension["extension"] == "transferFeeConfig")
+    })
+}
+
+fn parse_mint(mint: &str, program: &str, info: &Value, epoch: Option<u64>) -> Option<MintInfo> {
+    let extensions = info["extensions"]
+        .as_array()
+        .map(|extensions| parse_mint_extensions(extensions, epoch));
+
+    Some(MintInfo {
+        mint: mint.to_string(),
+        program: program.to_string(),
+        decimals: info["decimals"].as_u64()? as u8,
+        supply: info["supply"].as_str()?.parse().ok()?,
+        mint_authority: info["mintAuthority"].as_str().map(String::from),
+        freeze_authority: info["freezeAuthority"].as_str().map(String::from),
+        extensions,
+    })
+}
+
+/// Decodes the jsonParsed extensions of a mint. The transfer fee in force at `epoch` is
+/// picked, the newest one when the epoch is unknown
+fn parse_mint_extensions(extensions: &[Value], epoch: Option<u64>) -> TokenExtensions {
+    let mut parsed = TokenExtensions::default();
+
+    for extension in extensions {
+        let state = &extension["state"];
+
+        match extension["extension"].as_str().unwrap_or_default() {
+            "transferFeeConfig" => {
+                if let Some(config) = parse_state::<UiTransferFeeConfig>(state) {
+                    let fee = match epoch {
+                        Some(epoch) if epoch < config.newer_transfer_fee.epoch => {
+                            config.older_transfer_fee
+                        }
+                        _ => config.newer_transfer_fee,
+                    };
+                    parsed.transfer_fee = Some(TransferFee {
+                        basis_points: fee.transfer_fee_basis_points,
+                        maximum_fee: fee.maximum_fee,
+                        withheld_amount: None,
+                    });
+                }
+            }
+            "interestBearingConfig" => {
+                if let Some(config) = parse_state::<UiInterestBearingConfig>(state) {
+                    parsed.interest_bearing = Some(InterestBearing {
+                        rate_bps: config.current_rate,
+                        rate_authority: config.rate_authority,
+                        initialization_timestamp: config.initialization_timestamp,
+                        pre_update_average_rate: config.pre_update_average_rate,
+                        last_update_timestamp: config.last_update_timestamp,
+                    });
+                }
+            }
+            "nonTransferable" => parsed.non_transferable = true,
+            "permanentDelegate" => {
+                parsed.permanent_delegate =
+                    parse_state::<UiPermanentDelegate>(state).and_then(|state| state.delegate);
+            }
+            "defaultAccountState" => {
+                parsed.default_account_state =
+                    parse_state::<UiDefaultAccountState>(state).map(|state| state.account_state);
+            }
+            "metadataPointer" => {
+                parsed.metadata_pointer =
+                    parse_state::<UiMetadataPointer>(state).map(|state| MetadataPointer {
+                        authority: state.authority,
+                        metadata_address: state.metadata_address,
+                    });
+            }
+            "tokenMetadata" => {
+                parsed.token_metadata =
+                    parse_state::<TokenMetadata>(state).map(|metadata| TokenMetadata {
+                        name: clean_string(metadata.name),
+                        symbol: clean_string(metadata.symbol),
+                        uri: clean_string(metadata.uri),
+                        ..metadata
+                    });
+            }
+            "transferHook" => {
+                parsed.transfer_hook =
+                    parse_state::<UiTransferHook>(state).and_then(|state| state.program_id);
+            }
+            _ => {}
+        }
+    }
+
+    parsed.risks = get_risks(&parsed);
+    parsed
+}
+
+/// Adds the extensions stored on a token account to the ones of its mint
+pub fn with_account_extensions(
+    mut extensions: TokenExtensions,
+    account_extensions: &[Value],
+    decimals: u8,
+) -> TokenExtensions {
+    for extension in account_extensions {
+        match extension["extension"].as_str().unwrap_or_default() {
+            "transferFeeAmount" => {
+                let withheld = match parse_state::<UiTransferFeeAmount>(&extension["state"]) {
+                    Some(state) => state.withheld_amount,
+                    None => continue,
+                };
+
+                if let Some(transfer_fee) = extensions.transfer_fee.as_mut() {
+                    transfer_fee.withheld_amount = Some(FormattedAmount {
+                        amount: withheld.to_string(),
+                        formatted: withheld as f64 / 10_f64.powi(decimals as i32),
+                    });
+                }
+            }
+            "nonTransferableAccount" => extensions.non_transferable = true,
+            _ => {}
+        }
+    }
+
+    extensions.risks = get_risks(&extensions);
+    extensions
+}
+
+/// Transfer fees withheld in a Token-2022 account, from its jsonParsed extensions
+pub fn withheld_amount(account_extensions: &[Value]) -> u64 {
+    account_extensions
+        .iter()
+        .filter(|extension| extension["extension"].as_str() == Some("transferFeeAmount"))
+        .filter_map(|extension| parse_state::<UiTransferFeeAmount>(&extension["state"]))
+        .map(|state| state.withheld_amount)
+        .sum()
+}
+
+/// Converts a raw amount to its UI amount. For interest-bearing mints this includes
+/// the interest accrued until now, like Token-2022's `amount_to_ui_amount`
+pub fn amount_to_ui_amount(amount: u64, decimals: u8, extensions: Option<&TokenExtensions>) -> f64 {
+    let ui_amount = amount as f64 / 10_f64.powi(decimals as i32);
+
+    match extensions.and_then(|extensions| extensions.interest_bearing.as_ref()) {
+        Some(config) => ui_amount * interest_scale(config, unix_timestamp()),
+        None => ui_amount,
+    }
+}
+
+fn interest_scale(config: &InterestBearing, now: i64) -> f64 {
+    let pre_update_timespan = config
+        .last_update_timestamp
+        .saturating_sub(config.initialization_timestamp);
+    let post_update_timespan = now.saturating_sub(config.last_update_timestamp);
+
+    let pre_update_exp = (config.pre_update_average_rate as f64 * pre_update_timespan as f64
+        / SECONDS_PER_YEAR
+        / 10_000.0)
+        .exp();
+    let post_update_exp =
+        (config.rate_bps as f64 * post_update_timespan as f64 / SECONDS_PER_YEAR / 10_000.0).exp();
+
+    pre_update_exp * post_update_exp
+}
+
+fn get_risks(extensions: &TokenExtensions) -> Vec<ExtensionRisk> {
+    let mut risks = Vec::new();
+
+    if extensions.permanent_delegate.is_some() {
+        risks.push(ExtensionRisk::PermanentDelegate);
+    }
+    if matches!(&extensions.transfer_fee, Some(fee) if fee.basis_points > 0) {
+        risks.push(ExtensionRisk::TransferFee);
+    }
+    if extensions.non_transferable {
+        risks.push(ExtensionRisk::NonTransferable);
+    }
+    if extensions.default_account_state.as_deref() == Some("frozen") {
+        risks.push(ExtensionRisk::DefaultFrozen);
+    }
+    if extensions.transfer_hook.is_some() {
+        risks.push(ExtensionRisk::TransferHook);
+    }
+
+    risks
+}
+
+fn parse_state<T: DeserializeOwned>(state: &Value) -> Option<T> {
+    serde_json::from_value(state.clone()).ok()
+}
+
+fn unix_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
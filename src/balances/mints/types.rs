@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::FormattedAmount;
+
+/// Mint account state, as decoded from the jsonParsed RPC encoding
+#[derive(Default, Debug, Clone)]
+pub struct MintInfo {
+    pub mint: String,
+    /// Program that owns the mint, SPL Token or Token-2022
+    pub program: String,
+    pub decimals: u8,
+    pub supply: u64,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+    /// None for mints without Token-2022 extensions
+    pub extensions: Option<TokenExtensions>,
+}
+
+impl MintInfo {
+    /// Whether the mint can only ever have one indivisible token, like an NFT
+    pub fn is_nft(&self) -> bool {
+        self.decimals == 0 && self.supply == 1
+    }
+}
+
+/// Token-2022 extensions of a mint, merged with the ones of the token account when
+/// attached to a [`ParsedAta`](crate::ParsedAta)
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct TokenExtensions {
+    #[serde(rename = "transferFee", skip_serializing_if = "Option::is_none")]
+    pub transfer_fee: Option<TransferFee>,
+    #[serde(rename = "interestBearing", skip_serializing_if = "Option::is_none")]
+    pub interest_bearing: Option<InterestBearing>,
+    #[serde(rename = "nonTransferable")]
+    pub non_transferable: bool,
+    /// Address allowed to transfer or burn tokens from any account of the mint
+    #[serde(rename = "permanentDelegate", skip_serializing_if = "Option::is_none")]
+    pub permanent_delegate: Option<String>,
+    /// State new token accounts start in, `initialized` or `frozen`
+    #[serde(
+        rename = "defaultAccountState",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default_account_state: Option<String>,
+    #[serde(rename = "metadataPointer", skip_serializing_if = "Option::is_none")]
+    pub metadata_pointer: Option<MetadataPointer>,
+    /// Metadata stored on the mint itself
+    #[serde(rename = "tokenMetadata", skip_serializing_if = "Option::is_none")]
+    pub token_metadata: Option<TokenMetadata>,
+    /// Program invoked on every transfer
+    #[serde(rename = "transferHook", skip_serializing_if = "Option::is_none")]
+    pub transfer_hook: Option<String>,
+    /// Extensions that let someone other than the holder move, tax or lock the tokens
+    pub risks: Vec<ExtensionRisk>,
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct TransferFee {
+    #[serde(rename = "basisPoints")]
+    pub basis_points: u16,
+    #[serde(rename = "maximumFee")]
+    pub maximum_fee: u64,
+    /// Fees withheld in the token account, they are not part of the balance
+    #[serde(rename = "withheldAmount", skip_serializing_if = "Option::is_none")]
+    pub withheld_amount: Option<FormattedAmount>,
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct InterestBearing {
+    /// Current rate in basis points, can be negative
+    #[serde(rename = "rateBps")]
+    pub rate_bps: i16,
+    #[serde(rename = "rateAuthority")]
+    pub rate_authority: Option<String>,
+    #[serde(rename = "initializationTimestamp")]
+    pub initialization_timestamp: i64,
+    #[serde(rename = "preUpdateAverageRate")]
+    pub pre_update_average_rate: i16,
+    #[serde(rename = "lastUpdateTimestamp")]
+    pub last_update_timestamp: i64,
+}
+
+#[derive(Default, Debug, Clone, Serialize)]
+pub struct MetadataPointer {
+    pub authority: Option<String>,
+    #[serde(rename = "metadataAddress")]
+    pub metadata_address: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    #[serde(rename = "updateAuthority")]
+    pub update_authority: Option<String>,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    #[serde(rename = "additionalMetadata", default)]
+    pub additional_metadata: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ExtensionRisk {
+    /// A delegate can move or burn the tokens at any time
+    PermanentDelegate,
+    /// Transfers are taxed
+    TransferFee,
+    /// The tokens can never leave the account
+    NonTransferable,
+    /// New accounts start frozen until the freeze authority thaws them
+    DefaultFrozen,
+    /// Every transfer runs an arbitrary program, which may block it
+    TransferHook,
+}
+
+// jsonParsed extension states
+
+/// The newer fee only applies from its epoch on, the older one until then
+#[derive(Deserialize)]
+pub(crate) struct UiTransferFeeConfig {
+    #[serde(rename = "olderTransferFee")]
+    pub older_transfer_fee: UiTransferFee,
+    #[serde(rename = "newerTransferFee")]
+    pub newer_transfer_fee: UiTransferFee,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiTransferFee {
+    pub epoch: u64,
+    #[serde(rename = "maximumFee")]
+    pub maximum_fee: u64,
+    #[serde(rename = "transferFeeBasisPoints")]
+    pub transfer_fee_basis_points: u16,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiTransferFeeAmount {
+    #[serde(rename = "withheldAmount")]
+    pub withheld_amount: u64,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiInterestBearingConfig {
+    #[serde(rename = "rateAuthority")]
+    pub rate_authority: Option<String>,
+    #[serde(rename = "initializationTimestamp")]
+    pub initialization_timestamp: i64,
+    #[serde(rename = "preUpdateAverageRate")]
+    pub pre_update_average_rate: i16,
+    #[serde(rename = "lastUpdateTimestamp")]
+    pub last_update_timestamp: i64,
+    #[serde(rename = "currentRate")]
+    pub current_rate: i16,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiPermanentDelegate {
+    pub delegate: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiDefaultAccountState {
+    #[serde(rename = "accountState")]
+    pub account_state: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiMetadataPointer {
+    pub authority: Option<String>,
+    #[serde(rename = "metadataAddress")]
+    pub metadata_address: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiTransferHook {
+    #[serde(rename = "programId")]
+    pub program_id: Option<String>,
+}
@@ -1,3 +1,11 @@
 pub mod accounts;
+pub mod audit;
 pub mod dapps;
+pub mod mints;
+pub mod nfts;
+pub mod reclaim;
+pub mod risk;
+pub mod sol;
+pub mod spam;
+pub mod stake;
 pub mod types;
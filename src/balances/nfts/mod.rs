@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use futures::future::{join, join_all};
+use mpl_token_metadata::{accounts::Metadata, types::TokenStandard};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    balances::{
+        accounts::{get_accounts, types::ParsedMetadata},
+        mints::{fetch_mints, MintInfo},
+    },
+    client::{types::AccountData, GetMultipleAccountsConfig, SolanaMirrorRpcClient},
+    enums::Error,
+    metadata::{
+        metaplex::{get_edition_address, get_metadata_address, parse_edition, EditionAccount},
+        MetadataResolver,
+    },
+};
+
+pub use types::{EditionInfo, NftCollection, NftCollectionKey, NftCreator, NftStandard, ParsedNft};
+
+pub mod types;
+
+/// Metaplex accounts of an NFT mint
+struct NftAccounts {
+    metadata: Option<Metadata>,
+    edition: Option<EditionAccount>,
+}
+
+/// Fetches the NFTs held by the given address, grouped by verified collection.
+/// Collections are sorted by name and the NFTs without one come last
+pub async fn get_nfts(
+    client: &SolanaMirrorRpcClient,
+    resolver: &MetadataResolver,
+    address: &Pubkey,
+) -> Result<Vec<NftCollection>, Error> {
+    let accounts = get_accounts(client, address).await?;
+
+    let candidates: Vec<String> = accounts
+        .iter()
+        .filter(|account| {
+            let token_amount = &account.account.data.parsed.info.token_amount;
+            token_amount.amount == "1" && token_amount.decimals == 0
+        })
+        .map(|account| account.account.data.parsed.info.mint.clone())
+        .collect();
+    let mint_infos = fetch_mints(client, &candidates).await;
+
+    // A single token with no decimals is not enough, the supply must be one too
+    let accounts: Vec<&AccountData> = accounts
+        .iter()
+        .filter(|account| {
+            mint_infos
+                .get(&account.account.data.parsed.info.mint)
+                .is_some_and(MintInfo::is_nft)
+        })
+        .collect();
+    let mints: Vec<String> = accounts
+        .iter()
+        .map(|account| account.account.data.parsed.info.mint.clone())
+        .collect();
+
+    let (nft_accounts, metadata) = join(
+        get_nft_accounts(client, &mints),
+        resolver.resolve(client, &mints, &mint_infos),
+    )
+    .await;
+    let nft_accounts = nft_accounts?;
+
+    let nft_futures = accounts.into_iter().filter_map(|account| {
+        let mint = &account.account.data.parsed.info.mint;
+        let nft_accounts = nft_accounts.get(mint)?;
+        let token_standard = get_token_standard(nft_accounts)?;
+
+        Some(parse_nft(
+            client,
+            resolver,
+            account,
+            token_standard,
+            nft_accounts,
+            metadata.get(mint).cloned().unwrap_or_default(),
+        ))
+    });
+    let nfts = join_all(nft_futures).await;
+
+    Ok(group_by_collection(client, resolver, nfts).await)
+}
+
+/// Fetches the metadata and edition accounts of the mints, two per mint in a single call
+async fn get_nft_accounts(
+    client: &SolanaMirrorRpcClient,
+    mints: &[String],
+) -> Result<HashMap<String, NftAccounts>, Error> {
+    let mints: Vec<&String> = mints
+        .iter()
+        .filter(|mint| get_metadata_address(mint).is_some())
+        .collect();
+    if mints.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let pubkeys: Vec<Pubkey> = mints
+        .iter()
+        .flat_map(|mint| [get_metadata_address(mint), get_edition_address(mint)])
+        .flatten()
+        .collect();
+
+    let accounts = client
+        .get_multiple_accounts(
+            &pubkeys,
+            Some(GetMultipleAccountsConfig {
+                commitment: None,
+                encoding: Some("base64".to_string()),
+                data_slice: None,
+                min_context_slot: None,
+            }),
+        )
+        .await?;
+
+    Ok(mints
+        .into_iter()
+        .zip(accounts.chunks(2))
+        .map(|(mint, accounts)| {
+            let data = |index: usize| {
+                accounts
+                    .get(index)
+                    .and_then(|account| account.as_ref())
+                    .and_then(|account| account.decode_data())
+            };
+
+            (
+                mint.to_string(),
+                NftAccounts {
+                    metadata: data(0).and_then(|data| Metadata::safe_deserialize(&data).ok()),
+                    edition: data(1).and_then(|data| parse_edition(&data)),
+                },
+            )
+        })
+        .collect())
+}
+
+/// Token standard of the NFT. None for fungible assets, which may also have a supply of one,
+/// and for mints without Metaplex metadata. Legacy NFTs with no standard set are told apart
+/// by their edition
+fn get_token_standard(nft_accounts: &NftAccounts) -> Option<NftStandard> {
+    let metadata = nft_accounts.metadata.as_ref()?;
+
+    match metadata.token_standard {
+        Some(TokenStandard::NonFungible) => Some(NftStandard::NonFungible),
+        Some(TokenStandard::NonFungibleEdition) => Some(NftStandard::NonFungibleEdition),
+        Some(TokenStandard::ProgrammableNonFungible) => Some(NftStandard::ProgrammableNonFungible),
+        Some(TokenStandard::ProgrammableNonFungibleEdition) => {
+            Some(NftStandard::ProgrammableNonFungibleEdition)
+        }
+        Some(TokenStandard::Fungible) | Some(TokenStandard::FungibleAsset) => None,
+        None => match nft_accounts.edition {
+            Some(EditionAccount::Print(_)) => Some(NftStandard::NonFungibleEdition),
+            _ => Some(NftStandard::NonFungible),
+        },
+    }
+}
+
+/// Parses the given NFT account.
+async fn parse_nft(
+    client: &SolanaMirrorRpcClient,
+    resolver: &MetadataResolver,
+    account: &AccountData,
+    token_standard: NftStandard,
+    nft_accounts: &NftAccounts,
+    metadata: ParsedMetadata,
+) -> ParsedNft {
+    let mint = &account.account.data.parsed.info.mint;
+    let image = resolver.fetch_image(client, mint, &metadata).await;
+
+    let on_chain = nft_accounts.metadata.as_ref();
+    let edition = nft_accounts.edition.as_ref().map(|edition| match edition {
+        EditionAccount::Master(master) => EditionInfo::Master {
+            supply: master.supply,
+            max_supply: master.max_supply,
+        },
+        EditionAccount::Print(print) => EditionInfo::Print {
+            parent: print.parent.to_string(),
+            number: print.edition,
+        },
+    });
+
+    ParsedNft {
+        mint: mint.clone(),
+        ata: account.pubkey.clone(),
+        name: metadata.name,
+        symbol: metadata.symbol,
+        uri: metadata.uri,
+        image,
+        token_standard,
+        token_program: account.account.owner.clone(),
+        collection: on_chain
+            .and_then(|on_chain| on_chain.collection.as_ref())
+            .map(|collection| NftCollectionKey {
+                address: collection.key.to_string(),
+                verified: collection.verified,
+            }),
+        creators: on_chain
+            .and_then(|on_chain| on_chain.creators.as_ref())
+            .map(|creators| {
+                creators
+                    .iter()
+                    .map(|creator| NftCreator {
+                        address: creator.address.to_string(),
+                        verified: creator.verified,
+                        share: creator.share,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        royalty_basis_points: on_chain
+            .map(|on_chain| on_chain.seller_fee_basis_points)
+            .unwrap_or_default(),
+        primary_sale_happened: on_chain
+            .map(|on_chain| on_chain.primary_sale_happened)
+            .unwrap_or_default(),
+        is_mutable: on_chain
+            .map(|on_chain| on_chain.is_mutable)
+            .unwrap_or_default(),
+        update_authority: on_chain.map(|on_chain| on_chain.update_authority.to_string()),
+        edition,
+    }
+}
+
+/// Groups the NFTs by verified collection, resolving the name and image of each collection
+async fn group_by_collection(
+    client: &SolanaMirrorRpcClient,
+    resolver: &MetadataResolver,
+    nfts: Vec<ParsedNft>,
+) -> Vec<NftCollection> {
+    let mut grouped: HashMap<Option<String>, Vec<ParsedNft>> = HashMap::new();
+    for nft in nfts {
+        let collection = nft
+            .collection
+            .as_ref()
+            .filter(|collection| collection.verified)
+            .map(|collection| collection.address.clone());
+
+        grouped.entry(collection).or_default().push(nft);
+    }
+
+    let collection_mints: Vec<String> = grouped.keys().flatten().cloned().collect();
+    let metadata = resolver
+        .resolve(client, &collection_mints, &HashMap::new())
+        .await;
+
+    let collection_futures = grouped.into_iter().map(|(address, mut nfts)| {
+        let metadata = address
+            .as_ref()
+            .and_then(|address| metadata.get(address))
+            .cloned()
+            .unwrap_or_default();
+
+        async move {
+            let image = match &address {
+                Some(address) => resolver.fetch_image(client, address, &metadata).await,
+                None => String::new(),
+            };
+            nfts.sort_by(|a, b| a.name.cmp(&b.name));
+
+            NftCollection {
+                address,
+                name: metadata.name,
+                image,
+                nfts,
+            }
+        }
+    });
+
+    let mut collections = join_all(collection_futures).await;
+    collections.sort_by(|a, b| {
+        a.address
+            .is_none()
+            .cmp(&b.address.is_none())
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+
+    collections
+}
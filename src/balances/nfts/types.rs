@@ -0,0 +1,87 @@
+use serde::Serialize;
+
+/// Metaplex token standard of an NFT. Legacy NFTs created before token standards
+/// existed are reported as `NonFungible`, or `NonFungibleEdition` for prints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum NftStandard {
+    NonFungible,
+    NonFungibleEdition,
+    /// pNFT, transfers go through the token metadata program and its rule set
+    ProgrammableNonFungible,
+    ProgrammableNonFungibleEdition,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedNft {
+    pub mint: String,
+    pub ata: String,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub image: String,
+    #[serde(rename = "tokenStandard")]
+    pub token_standard: NftStandard,
+    /// Program that owns the token account, SPL Token or Token-2022
+    #[serde(rename = "tokenProgram")]
+    pub token_program: String,
+    /// Collection claimed by the metadata, verified or not
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection: Option<NftCollectionKey>,
+    pub creators: Vec<NftCreator>,
+    /// Royalties paid to the creators on secondary sales
+    #[serde(rename = "royaltyBasisPoints")]
+    pub royalty_basis_points: u16,
+    #[serde(rename = "primarySaleHappened")]
+    pub primary_sale_happened: bool,
+    #[serde(rename = "isMutable")]
+    pub is_mutable: bool,
+    #[serde(rename = "updateAuthority", skip_serializing_if = "Option::is_none")]
+    pub update_authority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edition: Option<EditionInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NftCollectionKey {
+    pub address: String,
+    /// Whether the collection authority signed off on the NFT being part of it
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NftCreator {
+    pub address: String,
+    /// Whether the creator signed the metadata, unverified creators may be fake
+    pub verified: bool,
+    /// Percentage of the royalties going to the creator
+    pub share: u8,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum EditionInfo {
+    /// Original that prints can be minted from
+    Master {
+        /// Prints minted so far
+        supply: u64,
+        /// None when unlimited prints can be minted
+        #[serde(rename = "maxSupply")]
+        max_supply: Option<u64>,
+    },
+    /// Numbered print of a master edition
+    Print {
+        /// Edition account of the master edition
+        parent: String,
+        number: u64,
+    },
+}
+
+/// NFTs grouped by verified collection
+#[derive(Debug, Clone, Serialize)]
+pub struct NftCollection {
+    /// Collection mint, None for the NFTs without a verified collection
+    pub address: Option<String>,
+    pub name: String,
+    pub image: String,
+    pub nfts: Vec<ParsedNft>,
+}
@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use base64::Engine;
+use futures::future::join;
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+
+use crate::{
+    balances::{accounts::get_accounts, mints::withheld_amount},
+    client::{types::AccountData, SolanaMirrorRpcClient},
+    consts::SOL_ADDRESS,
+    enums::Error,
+    price::get_price,
+    utils::lamports_to_sol,
+};
+
+pub use types::{ClosableAccount, CloseTransaction, RentReclaimReport};
+
+pub mod types;
+
+/// Accounts closed per transaction, which keeps transactions well under the size limit
+pub const MAX_CLOSE_PER_TRANSACTION: usize = 20;
+/// Index of the `CloseAccount` instruction, the same in SPL Token and Token-2022
+const CLOSE_ACCOUNT_INSTRUCTION: u8 = 9;
+
+/// Lists the token accounts of the given address that can be closed to reclaim their rent:
+/// empty, not frozen, without another close authority and without withheld Token-2022
+/// transfer fees
+pub async fn get_rent_reclaim_report(
+    client: &SolanaMirrorRpcClient,
+    address: &Pubkey,
+) -> Result<RentReclaimReport, Error> {
+    let (accounts, price) = join(
+        get_accounts(client, address),
+        get_price(client, Pubkey::from_str(SOL_ADDRESS).unwrap(), Some(9)),
+    )
+    .await;
+
+    let mut accounts: Vec<ClosableAccount> = accounts?.iter().filter_map(parse_closable).collect();
+    accounts.sort_by(|a, b| b.lamports.formatted.total_cmp(&a.lamports.formatted));
+
+    let total = lamports_to_sol(accounts.iter().map(lamports).sum());
+
+    Ok(RentReclaimReport {
+        accounts,
+        price,
+        total_value_usd: price.map(|price| price * total.formatted),
+        total,
+    })
+}
+
+/// Builds unsigned transactions closing the accounts, [`MAX_CLOSE_PER_TRANSACTION`] per
+/// transaction. The owner pays the fees, signs, and gets the rent back
+pub async fn build_close_transactions(
+    client: &SolanaMirrorRpcClient,
+    owner: &Pubkey,
+    accounts: &[ClosableAccount],
+) -> Result<Vec<CloseTransaction>, Error> {
+    if accounts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let latest_blockhash = client.get_latest_blockhash(None).await?.value;
+    let blockhash = Hash::from_str(&latest_blockhash.blockhash)
+        .map_err(|e| Error::ParseError(e.to_string()))?;
+
+    accounts
+        .chunks(MAX_CLOSE_PER_TRANSACTION)
+        .map(|batch| {
+            let instructions = batch
+                .iter()
+                .map(|account| close_account_instruction(account, owner))
+                .collect::<Result<Vec<Instruction>, Error>>()?;
+
+            let message = Message::new_with_blockhash(&instructions, Some(owner), &blockhash);
+            let transaction = Transaction::new_unsigned(message);
+            let serialized =
+                bincode::serialize(&transaction).map_err(|e| Error::ParseError(e.to_string()))?;
+
+            Ok(CloseTransaction {
+                transaction: base64::prelude::BASE64_STANDARD.encode(serialized),
+                accounts: batch
+                    .iter()
+                    .map(|account| account.address.clone())
+                    .collect(),
+                lamports: lamports_to_sol(batch.iter().map(lamports).sum()),
+                blockhash: latest_blockhash.blockhash.clone(),
+                last_valid_block_height: latest_blockhash.last_valid_block_height,
+            })
+        })
+        .collect()
+}
+
+/// Parses the account if it can be closed
+fn parse_closable(account: &AccountData) -> Option<ClosableAccount> {
+    let info = &account.account.data.parsed.info;
+
+    // Closing fails on frozen accounts, and on accounts still holding withheld fees.
+    // A close authority other than the owner would have to sign instead
+    if info.token_amount.amount != "0"
+        || info.state == "frozen"
+        || info
+            .close_authority
+            .as_ref()
+            .is_some_and(|close_authority| *close_authority != info.owner)
+        || withheld_amount(info.extensions.as_deref().unwrap_or_default()) > 0
+    {
+        return None;
+    }
+
+    Some(ClosableAccount {
+        address: account.pubkey.clone(),
+        mint: info.mint.clone(),
+        token_program: account.account.owner.clone(),
+        lamports: lamports_to_sol(account.account.lamports),
+    })
+}
+
+/// `CloseAccount` sending the rent to the owner, who is also the signing authority
+fn close_account_instruction(
+    account: &ClosableAccount,
+    owner: &Pubkey,
+) -> Result<Instruction, Error> {
+    let program_id = Pubkey::from_str(&account.token_program).map_err(|_| Error::InvalidAddress)?;
+    let address = Pubkey::from_str(&account.address).map_err(|_| Error::InvalidAddress)?;
+
+    Ok(Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(address, false),
+            AccountMeta::new(*owner, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: vec![CLOSE_ACCOUNT_INSTRUCTION],
+    })
+}
+
+fn lamports(account: &ClosableAccount) -> u64 {
+    account.lamports.amount.parse::<u64>().unwrap_or_default()
+}
@@ -0,0 +1,41 @@
+use serde::Serialize;
+
+use crate::types::FormattedAmount;
+
+/// Empty token account whose rent can be reclaimed by closing it
+#[derive(Debug, Clone, Serialize)]
+pub struct ClosableAccount {
+    pub address: String,
+    pub mint: String,
+    /// Program that owns the account, SPL Token or Token-2022
+    #[serde(rename = "tokenProgram")]
+    pub token_program: String,
+    /// Lamports sent back to the owner when the account is closed
+    pub lamports: FormattedAmount,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RentReclaimReport {
+    pub accounts: Vec<ClosableAccount>,
+    /// Lamports recoverable by closing every account
+    pub total: FormattedAmount,
+    /// Price of SOL in USD
+    pub price: Option<f64>,
+    #[serde(rename = "totalValueUsd")]
+    pub total_value_usd: Option<f64>,
+}
+
+/// Unsigned transaction closing a batch of token accounts
+#[derive(Debug, Clone, Serialize)]
+pub struct CloseTransaction {
+    /// Bincode serialized transaction, base64 encoded, with the owner as fee payer
+    pub transaction: String,
+    /// Accounts closed by the transaction
+    pub accounts: Vec<String>,
+    /// Lamports recovered by the transaction, before fees
+    pub lamports: FormattedAmount,
+    pub blockhash: String,
+    /// Sign and send the transaction before this block height, or build it again
+    #[serde(rename = "lastValidBlockHeight")]
+    pub last_valid_block_height: u64,
+}
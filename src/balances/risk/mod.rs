@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use futures::future::join;
+use mpl_token_metadata::accounts::Metadata;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    balances::{
+        accounts::{types::ParsedMetadata, ParsedAta},
+        mints::{amount_to_ui_amount, fetch_largest_accounts, fetch_mints, MintInfo},
+    },
+    client::{types::TokenLargestAccount, GetMultipleAccountsConfig, SolanaMirrorRpcClient},
+    consts::SYSTEM_PROGRAM_ID,
+    metadata::{metaplex::get_metadata_address, MetadataSource},
+    types::FormattedAmount,
+};
+
+pub use types::{HolderShare, MintRiskProfile};
+
+pub mod types;
+
+/// Number of largest accounts the concentration is measured on
+const TOP_HOLDERS: usize = 10;
+
+/// Builds the risk profile of each mint. Mints that don't exist are left out, and the
+/// concentration is left empty when `getTokenLargestAccounts` fails
+pub async fn get_mint_risk_profiles(
+    client: &SolanaMirrorRpcClient,
+    mints: &[String],
+) -> HashMap<String, MintRiskProfile> {
+    let mint_infos = fetch_mints(client, mints).await;
+    let mints: Vec<String> = mint_infos.keys().cloned().collect();
+
+    let (metaplex_mutable, largest_accounts) = join(
+        get_metaplex_mutable(client, &mints),
+        fetch_largest_accounts(client, &mints),
+    )
+    .await;
+
+    build_profiles(&mint_infos, &largest_accounts, &metaplex_mutable)
+}
+
+/// Attaches the risk profile of their mint to the token accounts, from the mints, metadata
+/// and largest accounts fetched with them. Native SOL is skipped
+pub(crate) async fn attach_mint_risk_profiles(
+    client: &SolanaMirrorRpcClient,
+    mut accounts: Vec<ParsedAta>,
+    mint_infos: &HashMap<String, MintInfo>,
+    metadata: &HashMap<String, ParsedMetadata>,
+    largest_accounts: &HashMap<String, Vec<TokenLargestAccount>>,
+) -> Vec<ParsedAta> {
+    let mut mutable: HashMap<String, bool> = metadata
+        .iter()
+        .filter_map(|(mint, metadata)| Some((mint.to_string(), metadata.mutable?)))
+        .collect();
+
+    // Metadata taken from the token list or overrides doesn't tell whether it is mutable,
+    // the other sources were already read on-chain
+    let unknown: Vec<String> = metadata
+        .iter()
+        .filter(|(mint, metadata)| {
+            metadata.mutable.is_none()
+                && matches!(
+                    metadata.source,
+                    MetadataSource::TokenList | MetadataSource::Override
+                )
+                && mint_infos.contains_key(*mint)
+        })
+        .map(|(mint, _)| mint.to_string())
+        .collect();
+    mutable.extend(get_metaplex_mutable(client, &unknown).await);
+
+    let mut profiles = build_profiles(mint_infos, largest_accounts, &mutable);
+    for account in accounts.iter_mut() {
+        if account.token_program != SYSTEM_PROGRAM_ID {
+            account.risk_profile = profiles.remove(&account.mint);
+        }
+    }
+
+    accounts
+}
+
+/// Mints the risk profiles are built from when they come with the token accounts
+pub(crate) fn risk_profile_mints(accounts: &[ParsedAta]) -> Vec<String> {
+    accounts
+        .iter()
+        .filter(|account| account.token_program != SYSTEM_PROGRAM_ID)
+        .map(|account| account.mint.clone())
+        .collect()
+}
+
+fn build_profiles(
+    mint_infos: &HashMap<String, MintInfo>,
+    largest_accounts: &HashMap<String, Vec<TokenLargestAccount>>,
+    metaplex_mutable: &HashMap<String, bool>,
+) -> HashMap<String, MintRiskProfile> {
+    mint_infos
+        .values()
+        .map(|mint_info| {
+            let metadata_mutable = metaplex_mutable.get(&mint_info.mint).copied().or_else(|| {
+                mint_info
+                    .extensions
+                    .as_ref()
+                    .and_then(|extensions| extensions.token_metadata.as_ref())
+                    .map(|token_metadata| token_metadata.update_authority.is_some())
+            });
+
+            (
+                mint_info.mint.clone(),
+                build_profile(
+                    mint_info,
+                    largest_accounts.get(&mint_info.mint),
+                    metadata_mutable,
+                ),
+            )
+        })
+        .collect()
+}
+
+fn build_profile(
+    mint_info: &MintInfo,
+    largest_accounts: Option<&Vec<TokenLargestAccount>>,
+    metadata_mutable: Option<bool>,
+) -> MintRiskProfile {
+    let supply = mint_info.supply;
+    let share = |amount: u64| (supply > 0).then(|| amount as f64 / supply as f64);
+
+    let top_holders: Vec<HolderShare> = largest_accounts
+        .map(|largest_accounts| {
+            largest_accounts
+                .iter()
+                .take(TOP_HOLDERS)
+                .map(|account| {
+                    let amount = account.amount.parse::<u64>().unwrap_or_default();
+                    HolderShare {
+                        address: account.address.clone(),
+                        amount: FormattedAmount {
+                            amount: account.amount.clone(),
+                            formatted: amount_to_ui_amount(
+                                amount,
+                                mint_info.decimals,
+                                mint_info.extensions.as_ref(),
+                            ),
+                        },
+                        share: share(amount).unwrap_or_default(),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let held = |holders: &[HolderShare]| -> u64 {
+        holders
+            .iter()
+            .map(|holder| holder.amount.amount.parse::<u64>().unwrap_or_default())
+            .sum()
+    };
+    let (top_holder_share, top_10_share) = if top_holders.is_empty() {
+        (None, None)
+    } else {
+        (share(held(&top_holders[..1])), share(held(&top_holders)))
+    };
+
+    MintRiskProfile {
+        mint: mint_info.mint.clone(),
+        mint_authority: mint_info.mint_authority.clone(),
+        freeze_authority: mint_info.freeze_authority.clone(),
+        supply: FormattedAmount {
+            amount: supply.to_string(),
+            formatted: amount_to_ui_amount(
+                supply,
+                mint_info.decimals,
+                mint_info.extensions.as_ref(),
+            ),
+        },
+        top_holders,
+        top_holder_share,
+        top_10_share,
+        metadata_mutable,
+    }
+}
+
+/// Reads `is_mutable` from the Metaplex metadata of the mints that have one
+async fn get_metaplex_mutable(
+    client: &SolanaMirrorRpcClient,
+    mints: &[String],
+) -> HashMap<String, bool> {
+    let mints: Vec<&String> = mints
+        .iter()
+        .filter(|mint| get_metadata_address(mint).is_some())
+        .collect();
+    if mints.is_empty() {
+        return HashMap::new();
+    }
+
+    let metadata_pubkeys: Vec<Pubkey> = mints
+        .iter()
+        .filter_map(|mint| get_metadata_address(mint))
+        .collect();
+
+    let accounts = match client
+        .get_multiple_accounts(
+            &metadata_pubkeys,
+            Some(GetMultipleAccountsConfig {
+                commitment: None,
+                encoding: Some("base64".to_string()),
+                data_slice: None,
+                min_context_slot: None,
+            }),
+        )
+        .await
+    {
+        Ok(accounts) => accounts,
+        Err(_) => return HashMap::new(),
+    };
+
+    mints
+        .into_iter()
+        .zip(accounts)
+        .filter_map(|(mint, account)| {
+            let metadata = Metadata::safe_deserialize(&account?.decode_data()?).ok()?;
+            Some((mint.to_string(), metadata.is_mutable))
+        })
+        .collect()
+}
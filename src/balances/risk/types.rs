@@ -0,0 +1,38 @@
+use serde::Serialize;
+
+use crate::types::FormattedAmount;
+
+/// What the issuer of a token can still do, and how concentrated its supply is
+#[derive(Debug, Clone, Serialize)]
+pub struct MintRiskProfile {
+    pub mint: String,
+    /// Address that can mint more tokens, None once minting is disabled
+    #[serde(rename = "mintAuthority")]
+    pub mint_authority: Option<String>,
+    /// Address that can freeze token accounts
+    #[serde(rename = "freezeAuthority")]
+    pub freeze_authority: Option<String>,
+    pub supply: FormattedAmount,
+    /// Largest token accounts of the mint, with their share of the supply
+    #[serde(rename = "topHolders")]
+    pub top_holders: Vec<HolderShare>,
+    /// Share of the supply in the largest account, from 0 to 1
+    #[serde(rename = "topHolderShare")]
+    pub top_holder_share: Option<f64>,
+    /// Share of the supply in the 10 largest accounts, from 0 to 1
+    #[serde(rename = "top10Share")]
+    pub top_10_share: Option<f64>,
+    /// Whether the name, symbol and uri can still be changed. None when the token has
+    /// no Metaplex or Token-2022 metadata
+    #[serde(rename = "metadataMutable")]
+    pub metadata_mutable: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HolderShare {
+    /// The token account, pools and exchanges hold through vault accounts
+    pub address: String,
+    pub amount: FormattedAmount,
+    /// Share of the supply, from 0 to 1
+    pub share: f64,
+}
@@ -0,0 +1,113 @@
+use std::str::FromStr;
+
+use futures::future::{join, join3};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    balances::{
+        accounts::get_accounts,
+        stake::{get_unvalued_stake_accounts, ParsedStakeAccount},
+    },
+    client::{types::AccountData, SolanaMirrorRpcClient},
+    consts::SOL_ADDRESS,
+    enums::Error,
+    price::get_price,
+    utils::lamports_to_sol,
+};
+
+pub use types::SolBreakdown;
+
+pub mod types;
+
+/// Splits the SOL owned by the given address into its native balance, wrapped SOL,
+/// rent locked in token accounts and staked SOL. Staked SOL is left out when the node
+/// doesn't serve `getProgramAccounts`
+pub async fn get_sol_breakdown(
+    client: &SolanaMirrorRpcClient,
+    address: &Pubkey,
+) -> Result<SolBreakdown, Error> {
+    let (holdings, stake_accounts, price) = join3(
+        get_sol_holdings(client, address),
+        get_unvalued_stake_accounts(client, address),
+        get_price(client, Pubkey::from_str(SOL_ADDRESS).unwrap(), Some(9)),
+    )
+    .await;
+    let (native, accounts) = holdings?;
+
+    Ok(build_sol_breakdown(
+        address,
+        native,
+        &accounts,
+        stake_accounts.ok().as_deref(),
+        price,
+    ))
+}
+
+/// Fetches the native balance and the token accounts the breakdown is built from
+pub(crate) async fn get_sol_holdings(
+    client: &SolanaMirrorRpcClient,
+    address: &Pubkey,
+) -> Result<(u64, Vec<AccountData>), Error> {
+    let (native, accounts) = join(
+        client.get_balance(address, None),
+        get_accounts(client, address),
+    )
+    .await;
+
+    Ok((native?, accounts?))
+}
+
+/// Builds the breakdown from already fetched holdings. Staked SOL is left out when
+/// `stake_accounts` is None
+pub(crate) fn build_sol_breakdown(
+    address: &Pubkey,
+    native: u64,
+    accounts: &[AccountData],
+    stake_accounts: Option<&[ParsedStakeAccount]>,
+    price: Option<f64>,
+) -> SolBreakdown {
+    let mut wrapped: u64 = 0;
+    let mut rent_locked: u64 = 0;
+    for account in accounts.iter() {
+        let info = &account.account.data.parsed.info;
+        let lamports = account.account.lamports;
+
+        // The lamports of a wSOL account are its balance plus its rent exempt reserve
+        if info.is_native && info.mint == SOL_ADDRESS {
+            let amount = info.token_amount.amount.parse::<u64>().unwrap_or_default();
+            wrapped += amount.min(lamports);
+            rent_locked += lamports.saturating_sub(amount);
+        } else {
+            rent_locked += lamports;
+        }
+    }
+
+    // Only the withdrawer owns the lamports, a staker can just delegate them
+    let address = address.to_string();
+    let staked: Option<u64> = stake_accounts.map(|stake_accounts| {
+        stake_accounts
+            .iter()
+            .filter(|stake_account| stake_account.withdrawer == address)
+            .map(|stake_account| {
+                stake_account
+                    .balance
+                    .amount
+                    .parse::<u64>()
+                    .unwrap_or_default()
+            })
+            .sum()
+    });
+
+    let total = lamports_to_sol(native + wrapped + rent_locked + staked.unwrap_or_default());
+
+    SolBreakdown {
+        native: lamports_to_sol(native),
+        wrapped: lamports_to_sol(wrapped),
+        rent_locked: lamports_to_sol(rent_locked),
+        staked: staked.map(lamports_to_sol),
+        token_accounts: accounts.len(),
+        price,
+        total_value_usd: price.map(|price| price * total.formatted),
+        total,
+    }
+}
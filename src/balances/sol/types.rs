@@ -0,0 +1,26 @@
+use serde::Serialize;
+
+use crate::types::FormattedAmount;
+
+/// Every lamport owned by an address, wherever it sits
+#[derive(Debug, Clone, Serialize)]
+pub struct SolBreakdown {
+    /// Balance of the wallet itself
+    pub native: FormattedAmount,
+    /// Wrapped SOL held in token accounts, their rent excluded
+    pub wrapped: FormattedAmount,
+    /// Lamports keeping token accounts rent exempt, reclaimed when they are closed
+    #[serde(rename = "rentLocked")]
+    pub rent_locked: FormattedAmount,
+    /// Lamports in stake accounts the address is the withdrawer of. None when the stake
+    /// accounts couldn't be fetched
+    pub staked: Option<FormattedAmount>,
+    pub total: FormattedAmount,
+    /// Number of token accounts holding rent
+    #[serde(rename = "tokenAccounts")]
+    pub token_accounts: usize,
+    /// Price of SOL in USD
+    pub price: Option<f64>,
+    #[serde(rename = "totalValueUsd")]
+    pub total_value_usd: Option<f64>,
+}
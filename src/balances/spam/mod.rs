@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use crate::{
+    balances::{accounts::ParsedAta, mints::MintInfo},
+    client::types::TokenLargestAccount,
+    consts::SYSTEM_PROGRAM_ID,
+    metadata::MetadataResolver,
+};
+
+pub use types::{SpamPolicy, SpamSignal, SpamVerdict};
+
+pub mod types;
+
+/// Top-level domains that make a `word.tld` in a name look like a link
+const LINK_TLDS: [&str; 24] = [
+    "com", "net", "org", "io", "xyz", "app", "gg", "co", "me", "info", "site", "online", "top",
+    "fun", "live", "pro", "vip", "club", "link", "click", "ru", "cc", "ly", "to",
+];
+
+/// Scores holdings with on-chain signals to spot airdropped scam tokens
+#[derive(Debug, Clone)]
+pub struct SpamClassifier {
+    policy: SpamPolicy,
+    /// Score from which a holding is considered spam
+    threshold: u8,
+    /// Tokens held by fewer accounts get the [`SpamSignal::FewHolders`] signal
+    min_holders: usize,
+}
+
+impl Default for SpamClassifier {
+    fn default() -> Self {
+        Self {
+            policy: SpamPolicy::Ignore,
+            threshold: 50,
+            min_holders: 5,
+        }
+    }
+}
+
+impl SpamClassifier {
+    pub fn new(policy: SpamPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: u8) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Holder count under which a token is suspicious. Counted among the 20 largest
+    /// accounts returned by `getTokenLargestAccounts`, so values above 20 have no effect
+    pub fn with_min_holders(mut self, min_holders: usize) -> Self {
+        self.min_holders = min_holders;
+        self
+    }
+
+    pub fn policy(&self) -> SpamPolicy {
+        self.policy
+    }
+
+    /// Mints whose largest accounts the classifier needs to count holders. Counting them
+    /// costs a request per mint, so only unverified fungible tokens are counted
+    pub fn holder_count_mints(
+        &self,
+        accounts: &[ParsedAta],
+        mint_infos: &HashMap<String, MintInfo>,
+    ) -> Vec<String> {
+        if self.policy == SpamPolicy::Ignore {
+            return Vec::new();
+        }
+
+        accounts
+            .iter()
+            .filter(|account| account.token_program != SYSTEM_PROGRAM_ID)
+            .filter(|account| !account.verified)
+            .filter(|account| !is_nft(mint_infos.get(&account.mint)))
+            .map(|account| account.mint.clone())
+            .collect()
+    }
+
+    /// Classifies the holdings following the policy: attaches a verdict to each one,
+    /// and drops the spam when hiding it. `mint_infos` are the mints of the holdings and
+    /// `largest_accounts` the largest accounts of [`SpamClassifier::holder_count_mints`],
+    /// as fetched with them
+    pub fn apply(
+        &self,
+        resolver: &MetadataResolver,
+        mint_infos: &HashMap<String, MintInfo>,
+        largest_accounts: &HashMap<String, Vec<TokenLargestAccount>>,
+        mut accounts: Vec<ParsedAta>,
+    ) -> Vec<ParsedAta> {
+        if self.policy == SpamPolicy::Ignore {
+            return accounts;
+        }
+
+        let verdicts = self.classify(resolver, mint_infos, largest_accounts, &accounts);
+        for account in accounts.iter_mut() {
+            account.spam = verdicts.get(&account.mint).cloned();
+        }
+
+        if self.policy == SpamPolicy::Hide {
+            accounts.retain(|account| !matches!(&account.spam, Some(verdict) if verdict.is_spam));
+        }
+
+        accounts
+    }
+
+    /// Returns a verdict per mint. Native SOL is never classified
+    pub fn classify(
+        &self,
+        resolver: &MetadataResolver,
+        mint_infos: &HashMap<String, MintInfo>,
+        largest_accounts: &HashMap<String, Vec<TokenLargestAccount>>,
+        accounts: &[ParsedAta],
+    ) -> HashMap<String, SpamVerdict> {
+        accounts
+            .iter()
+            .filter(|account| account.token_program != SYSTEM_PROGRAM_ID)
+            .map(|account| {
+                let mint_info = mint_infos.get(&account.mint);
+                let holders = largest_accounts.get(&account.mint).map(|largest_accounts| {
+                    largest_accounts
+                        .iter()
+                        .filter(|account| account.amount != "0")
+                        .count()
+                });
+                let signals =
+                    self.get_signals(account, mint_info, holders, resolver.has_token_registry());
+
+                (account.mint.clone(), self.verdict(account, signals))
+            })
+            .collect()
+    }
+
+    fn get_signals(
+        &self,
+        account: &ParsedAta,
+        mint_info: Option<&MintInfo>,
+        holders: Option<usize>,
+        has_registry: bool,
+    ) -> Vec<SpamSignal> {
+        let mut signals = Vec::new();
+
+        if let Some(mint_info) = mint_info {
+            if mint_info.mint_authority.is_some() {
+                signals.push(SpamSignal::MintAuthority);
+            }
+            if mint_info.freeze_authority.is_some() {
+                signals.push(SpamSignal::FreezeAuthority);
+            }
+        }
+
+        let permanent_delegate = account
+            .extensions
+            .as_ref()
+            .is_some_and(|extensions| extensions.permanent_delegate.is_some());
+        if permanent_delegate {
+            signals.push(SpamSignal::PermanentDelegate);
+        }
+
+        if contains_url(&account.name) || contains_url(&account.symbol) {
+            signals.push(SpamSignal::UrlInName);
+        }
+
+        // NFTs have no quote and a single holder by design. A quote that failed says
+        // nothing about liquidity, only Jupiter finding no route does
+        if !is_nft(mint_info) {
+            if account.no_route {
+                signals.push(SpamSignal::NoLiquidity);
+            }
+            if matches!(holders, Some(holders) if holders < self.min_holders) {
+                signals.push(SpamSignal::FewHolders);
+            }
+        }
+
+        // Without a registry nothing is verified, which says nothing about the token
+        if has_registry && !account.verified {
+            signals.push(SpamSignal::Unverified);
+        }
+
+        signals
+    }
+
+    fn verdict(&self, account: &ParsedAta, signals: Vec<SpamSignal>) -> SpamVerdict {
+        let score = signals
+            .iter()
+            .map(|signal| signal.weight() as u32)
+            .sum::<u32>()
+            .min(100) as u8;
+
+        SpamVerdict {
+            score,
+            // Listed tokens are trusted whatever their score
+            is_spam: !account.verified && score >= self.threshold,
+            signals,
+        }
+    }
+}
+
+fn is_nft(mint_info: Option<&MintInfo>) -> bool {
+    mint_info.is_some_and(MintInfo::is_nft)
+}
+
+/// Whether the text holds a link: a scheme, `www.`, a `t.me/` handle or a domain with one
+/// of [`LINK_TLDS`], e.g. `claim-now.xyz`. Names like `Mr.Bean` or `.sol` domains don't count
+fn contains_url(text: &str) -> bool {
+    let text = text.to_lowercase();
+
+    if text.contains("http://")
+        || text.contains("https://")
+        || text.contains("www.")
+        || text.contains("t.me/")
+    {
+        return true;
+    }
+
+    text.split(|c: char| c.is_whitespace() || c == '/')
+        .any(|word| match word.rsplit_once('.') {
+            Some((domain, tld)) => {
+                !domain.is_empty()
+                    && domain
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+                    && LINK_TLDS.contains(&tld)
+            }
+            None => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pyusd() -> (ParsedAta, MintInfo) {
+        let account = ParsedAta {
+            mint: "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo".to_string(),
+            name: "PayPal USD".to_string(),
+            symbol: "PYUSD".to_string(),
+            ..ParsedAta::default()
+        };
+        let mint_info = MintInfo {
+            mint: account.mint.clone(),
+            decimals: 6,
+            supply: 1_000_000_000,
+            mint_authority: Some("22mKJkKjGEQ3rampp5YKaSsaYZ52BUkcnUN6evXGsXzz".to_string()),
+            freeze_authority: None,
+            ..MintInfo::default()
+        };
+
+        (account, mint_info)
+    }
+
+    #[test]
+    fn only_definitive_signals_count() {
+        let classifier = SpamClassifier::new(SpamPolicy::Flag);
+        let (mut account, mint_info) = pyusd();
+
+        // A failed quote and the lack of a registry say nothing about the token
+        let signals = classifier.get_signals(&account, Some(&mint_info), Some(20), false);
+        assert_eq!(signals, vec![SpamSignal::MintAuthority]);
+
+        account.no_route = true;
+        let signals = classifier.get_signals(&account, Some(&mint_info), Some(2), true);
+        assert_eq!(
+            signals,
+            vec![
+                SpamSignal::MintAuthority,
+                SpamSignal::NoLiquidity,
+                SpamSignal::FewHolders,
+                SpamSignal::Unverified,
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_links_in_names() {
+        for name in [
+            "Claim at claim-now.xyz",
+            "https://airdrop",
+            "www.reward",
+            "t.me/drop",
+        ] {
+            assert!(contains_url(name), "{}", name);
+        }
+        for name in ["Mr.Bean", "bonk.sol", "U.S. Dollar", "Jupiter"] {
+            assert!(!contains_url(name), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use serde::Serialize;
+
+/// What to do with holdings classified as spam
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpamPolicy {
+    /// Don't classify holdings, no extra requests are made
+    #[default]
+    Ignore,
+    /// Attach a [`SpamVerdict`] to every holding
+    Flag,
+    /// Attach a [`SpamVerdict`] to every holding and drop the ones classified as spam
+    Hide,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SpamSignal {
+    /// More tokens can still be minted
+    MintAuthority,
+    /// The issuer can freeze the account
+    FreezeAuthority,
+    /// A Token-2022 delegate can move or burn the tokens
+    PermanentDelegate,
+    /// The name or symbol contains a link, typical of phishing airdrops
+    UrlInName,
+    /// No route to USDC was found, the token can't be sold
+    NoLiquidity,
+    /// The token is not in the token registry
+    Unverified,
+    /// Only a handful of accounts hold the token
+    FewHolders,
+}
+
+impl SpamSignal {
+    /// How much the signal adds to the spam score
+    pub fn weight(&self) -> u8 {
+        match self {
+            SpamSignal::MintAuthority => 10,
+            SpamSignal::FreezeAuthority => 15,
+            SpamSignal::PermanentDelegate => 40,
+            SpamSignal::UrlInName => 60,
+            SpamSignal::NoLiquidity => 30,
+            SpamSignal::Unverified => 10,
+            SpamSignal::FewHolders => 25,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpamVerdict {
+    /// Sum of the weights of the signals, capped at 100
+    pub score: u8,
+    pub signals: Vec<SpamSignal>,
+    /// Whether the score reached the classifier's threshold
+    #[serde(rename = "isSpam")]
+    pub is_spam: bool,
+}
@@ -0,0 +1,192 @@
+use std::{
+    collections::HashSet,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::future::{join, join3};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    client::{
+        types::{KeyedAccount, UiAccountData},
+        GetProgramAccountsConfig, RpcFilter, SolanaMirrorRpcClient,
+    },
+    consts::{SOL_ADDRESS, STAKE_PROGRAM_ID},
+    enums::Error,
+    price::get_price,
+    utils::lamports_to_sol,
+};
+
+pub use types::{ParsedStakeAccount, StakeActivation, StakeLockup};
+use types::{UiDelegation, UiStakeAccount};
+
+pub mod types;
+
+/// Size of a stake account, every state is padded to it
+const STAKE_ACCOUNT_SIZE: u64 = 200;
+/// Offset of the staker authority, after the state tag and the rent exempt reserve
+const STAKER_OFFSET: usize = 12;
+/// Offset of the withdrawer authority, right after the staker
+const WITHDRAWER_OFFSET: usize = 44;
+
+/// Fetches the stake accounts the given address can delegate or withdraw, valued in
+/// SOL and USD. Largest accounts come first
+pub async fn get_stake_accounts(
+    client: &SolanaMirrorRpcClient,
+    address: &Pubkey,
+) -> Result<Vec<ParsedStakeAccount>, Error> {
+    let (stake_accounts, price) = join(
+        get_unvalued_stake_accounts(client, address),
+        get_price(client, Pubkey::from_str(SOL_ADDRESS).unwrap(), Some(9)),
+    )
+    .await;
+
+    Ok(with_price(stake_accounts?, price))
+}
+
+/// Like [`get_stake_accounts`], without their USD value, for callers that already
+/// have the SOL price or don't need it
+pub(crate) async fn get_unvalued_stake_accounts(
+    client: &SolanaMirrorRpcClient,
+    address: &Pubkey,
+) -> Result<Vec<ParsedStakeAccount>, Error> {
+    let (by_staker, by_withdrawer, epoch_info) = join3(
+        get_accounts_by_authority(client, address, STAKER_OFFSET),
+        get_accounts_by_authority(client, address, WITHDRAWER_OFFSET),
+        client.get_epoch_info(None),
+    )
+    .await;
+    let epoch = epoch_info?.epoch;
+
+    // The staker is often the withdrawer too, so most accounts come back twice
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut stake_accounts: Vec<ParsedStakeAccount> = by_staker?
+        .into_iter()
+        .chain(by_withdrawer?)
+        .filter(|account| seen.insert(account.pubkey.clone()))
+        .filter_map(|account| parse_stake_account(&account, epoch))
+        .collect();
+
+    stake_accounts.sort_by(|a, b| b.balance.formatted.total_cmp(&a.balance.formatted));
+    Ok(stake_accounts)
+}
+
+/// Values the stake accounts at the given SOL price
+pub(crate) fn with_price(
+    stake_accounts: Vec<ParsedStakeAccount>,
+    price: Option<f64>,
+) -> Vec<ParsedStakeAccount> {
+    stake_accounts
+        .into_iter()
+        .map(|stake_account| ParsedStakeAccount {
+            price,
+            total_value_usd: price.map(|price| price * stake_account.balance.formatted),
+            ..stake_account
+        })
+        .collect()
+}
+
+/// Fetches the stake accounts that store the address at the given authority offset
+async fn get_accounts_by_authority(
+    client: &SolanaMirrorRpcClient,
+    address: &Pubkey,
+    offset: usize,
+) -> Result<Vec<KeyedAccount>, Error> {
+    let config = GetProgramAccountsConfig::default()
+        .with_encoding("jsonParsed")
+        .with_filter(RpcFilter::data_size(STAKE_ACCOUNT_SIZE))
+        .with_filter(RpcFilter::memcmp_pubkey(offset, address));
+
+    let accounts = client
+        .get_program_accounts(&Pubkey::from_str(STAKE_PROGRAM_ID).unwrap(), Some(config))
+        .await?;
+
+    Ok(accounts.into_accounts())
+}
+
+/// Parses the given stake account, left unvalued. None for uninitialized accounts
+fn parse_stake_account(account: &KeyedAccount, epoch: u64) -> Option<ParsedStakeAccount> {
+    let parsed = match &account.account.data {
+        UiAccountData::Json(data) => {
+            serde_json::from_value::<UiStakeAccount>(data.parsed.clone()).ok()?
+        }
+        UiAccountData::Binary(_, _) => return None,
+    };
+    let meta = parsed.info.meta;
+    let delegation = parsed.info.stake.map(|stake| stake.delegation);
+
+    let activation_epoch = delegation
+        .as_ref()
+        .and_then(|delegation| delegation.activation_epoch.parse::<u64>().ok());
+    let deactivation_epoch = delegation
+        .as_ref()
+        .and_then(|delegation| delegation.deactivation_epoch.parse::<u64>().ok())
+        .filter(|deactivation_epoch| *deactivation_epoch != u64::MAX);
+    let delegated_stake = delegation
+        .as_ref()
+        .and_then(|delegation| delegation.stake.parse::<u64>().ok())
+        .unwrap_or_default();
+    let rent_exempt_reserve = meta.rent_exempt_reserve.parse::<u64>().unwrap_or_default();
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+    let lockup = StakeLockup {
+        in_force: meta.lockup.unix_timestamp > now || meta.lockup.epoch > epoch,
+        unix_timestamp: meta.lockup.unix_timestamp,
+        epoch: meta.lockup.epoch,
+        custodian: meta.lockup.custodian,
+    };
+
+    let balance = lamports_to_sol(account.account.lamports);
+
+    Some(ParsedStakeAccount {
+        address: account.pubkey.clone(),
+        staker: meta.authorized.staker,
+        withdrawer: meta.authorized.withdrawer,
+        activation: get_activation(delegation.as_ref(), epoch),
+        vote_account: delegation.map(|delegation| delegation.voter),
+        activation_epoch,
+        deactivation_epoch,
+        delegated_stake: lamports_to_sol(delegated_stake),
+        rent_exempt_reserve: lamports_to_sol(rent_exempt_reserve),
+        lockup,
+        balance,
+        price: None,
+        total_value_usd: None,
+    })
+}
+
+/// Activation of the stake at the given epoch. Stake moves in one epoch, the network-wide
+/// warmup and cooldown limits are ignored
+fn get_activation(delegation: Option<&UiDelegation>, epoch: u64) -> StakeActivation {
+    let delegation = match delegation {
+        Some(delegation) => delegation,
+        None => return StakeActivation::Inactive,
+    };
+
+    let activation_epoch = delegation
+        .activation_epoch
+        .parse::<u64>()
+        .unwrap_or(u64::MAX);
+    let deactivation_epoch = delegation
+        .deactivation_epoch
+        .parse::<u64>()
+        .unwrap_or(u64::MAX);
+
+    if deactivation_epoch != u64::MAX {
+        // Deactivated in the epoch it was activated, the stake never became active
+        if deactivation_epoch < epoch || deactivation_epoch == activation_epoch {
+            StakeActivation::Inactive
+        } else {
+            StakeActivation::Deactivating
+        }
+    } else if activation_epoch == epoch {
+        StakeActivation::Activating
+    } else {
+        // Bootstrap stakes from genesis have an activation epoch of u64::MAX
+        StakeActivation::Active
+    }
+}
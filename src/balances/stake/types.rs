@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::FormattedAmount;
+
+/// Activation of the delegated stake at the current epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum StakeActivation {
+    /// Not delegated, or fully cooled down
+    Inactive,
+    /// Delegated this epoch, starts earning rewards from the next one
+    Activating,
+    Active,
+    /// Deactivated this epoch, can be withdrawn from the next one
+    Deactivating,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedStakeAccount {
+    pub address: String,
+    /// Authority allowed to delegate and deactivate the stake
+    pub staker: String,
+    /// Authority allowed to withdraw the lamports
+    pub withdrawer: String,
+    pub activation: StakeActivation,
+    /// Validator vote account the stake is delegated to
+    #[serde(rename = "voteAccount")]
+    pub vote_account: Option<String>,
+    #[serde(rename = "activationEpoch")]
+    pub activation_epoch: Option<u64>,
+    #[serde(rename = "deactivationEpoch")]
+    pub deactivation_epoch: Option<u64>,
+    /// Lamports delegated to the validator, rewards included
+    #[serde(rename = "delegatedStake")]
+    pub delegated_stake: FormattedAmount,
+    /// Lamports kept in the account to keep it rent exempt
+    #[serde(rename = "rentExemptReserve")]
+    pub rent_exempt_reserve: FormattedAmount,
+    pub lockup: StakeLockup,
+    /// Every lamport in the account, in SOL
+    pub balance: FormattedAmount,
+    /// Price of SOL in USD
+    pub price: Option<f64>,
+    #[serde(rename = "totalValueUsd")]
+    pub total_value_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StakeLockup {
+    #[serde(rename = "unixTimestamp")]
+    pub unix_timestamp: i64,
+    pub epoch: u64,
+    /// Authority allowed to withdraw or change the lockup before it ends
+    pub custodian: String,
+    /// Whether the stake can't be withdrawn yet
+    #[serde(rename = "inForce")]
+    pub in_force: bool,
+}
+
+// jsonParsed stake account state
+
+#[derive(Deserialize)]
+pub(crate) struct UiStakeAccount {
+    pub info: UiStakeInfo,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiStakeInfo {
+    pub meta: UiStakeMeta,
+    pub stake: Option<UiStake>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiStakeMeta {
+    #[serde(rename = "rentExemptReserve")]
+    pub rent_exempt_reserve: String,
+    pub authorized: UiAuthorized,
+    pub lockup: UiLockup,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiAuthorized {
+    pub staker: String,
+    pub withdrawer: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiLockup {
+    #[serde(rename = "unixTimestamp")]
+    pub unix_timestamp: i64,
+    pub epoch: u64,
+    pub custodian: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UiStake {
+    pub delegation: UiDelegation,
+}
+
+/// Amounts and epochs are u64 values encoded as strings
+#[derive(Deserialize)]
+pub(crate) struct UiDelegation {
+    pub voter: String,
+    pub stake: String,
+    #[serde(rename = "activationEpoch")]
+    pub activation_epoch: String,
+    #[serde(rename = "deactivationEpoch")]
+    pub deactivation_epoch: String,
+}
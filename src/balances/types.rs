@@ -1,8 +1,18 @@
-use super::{accounts::types::ParsedAta, dapps::types::ParsedPosition};
+use super::{
+    accounts::types::ParsedAta, dapps::types::ParsedPosition, sol::SolBreakdown,
+    stake::ParsedStakeAccount,
+};
 
 #[derive(serde::Serialize)]
 pub struct BalancesResponse {
     pub accounts: Vec<ParsedAta>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raydium: Option<Vec<ParsedPosition>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stake: Option<Vec<ParsedStakeAccount>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sol: Option<SolBreakdown>,
+    /// USD value of the token accounts with a price, the positions and the stake accounts
+    #[serde(rename = "totalValueUsd")]
+    pub total_value_usd: f64,
 }
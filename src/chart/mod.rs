@@ -16,7 +16,7 @@ pub async fn get_chart_data(
     range: u8,
     timeframe: Timeframe,
 ) -> Result<Vec<ChartData>, Error> {
-    let txs = get_parsed_transactions(&client, address, None).await?;
+    let txs = get_parsed_transactions(client, address, None).await?;
     let balance_states = get_balance_states(&txs.transactions);
     let filtered_balance_states = filter_balance_states(&balance_states, timeframe, range);
 
@@ -24,7 +24,7 @@ pub async fn get_chart_data(
 }
 
 /// Creates a series of states with the balances of a wallet at each transaction
-fn get_balance_states(txs: &Vec<ParsedTransaction>) -> Vec<ChartData> {
+fn get_balance_states(txs: &[ParsedTransaction]) -> Vec<ChartData> {
     let mut states: Vec<ChartData> = Vec::with_capacity(txs.len());
 
     for tx in txs {
@@ -51,11 +51,7 @@ fn get_balance_states(txs: &Vec<ParsedTransaction>) -> Vec<ChartData> {
     states
 }
 
-fn filter_balance_states(
-    states: &Vec<ChartData>,
-    timeframe: Timeframe,
-    range: u8,
-) -> Vec<ChartData> {
+fn filter_balance_states(states: &[ChartData], timeframe: Timeframe, range: u8) -> Vec<ChartData> {
     if states.is_empty() {
         return Vec::new();
     }
@@ -0,0 +1,267 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::transport::{request_key, Document, Transport};
+use crate::enums::Error;
+
+/// A single recorded exchange. RPC calls are keyed by method and params,
+/// HTTP GETs by their url
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Interaction {
+    pub key: String,
+    pub response: Value,
+}
+
+/// Wraps a transport and appends every request/response pair to a cassette file.
+/// The file holds one JSON-encoded [`Interaction`] per line
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    file: Mutex<File>,
+}
+
+impl RecordingTransport {
+    /// Starts a fresh cassette at `path`, truncating any previous recording
+    pub fn new<P: AsRef<Path>>(inner: Arc<dyn Transport>, path: P) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)
+            .map_err(|e| Error::FetchError(e.to_string()))?;
+
+        Ok(Self {
+            inner,
+            file: Mutex::new(file),
+        })
+    }
+
+    fn record(&self, key: String, response: &Value) -> Result<(), Error> {
+        let line = serde_json::to_string(&Interaction {
+            key,
+            response: response.clone(),
+        })
+        .map_err(|e| Error::ParseError(e.to_string()))?;
+
+        let mut file = self.file.lock().unwrap();
+        writeln!(file, "{}", line).map_err(|e| Error::FetchError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl Transport for RecordingTransport {
+    async fn send(&self, request: &Value) -> Result<Value, Error> {
+        let response = self.inner.send(request).await?;
+        self.record(rpc_key(request), &response)?;
+        Ok(response)
+    }
+
+    async fn send_batch(&self, requests: &[Value]) -> Result<Value, Error> {
+        let response = self.inner.send_batch(requests).await?;
+
+        // Record each entry on its own so replays don't depend on how calls were batched
+        if let Value::Array(entries) = &response {
+            for request in requests {
+                if let Some(entry) = entries.iter().find(|entry| entry["id"] == request["id"]) {
+                    self.record(rpc_key(request), entry)?;
+                }
+            }
+        }
+
+        Ok(response)
+    }
+
+    async fn get_json(&self, url: &str) -> Result<Value, Error> {
+        let response = self.inner.get_json(url).await?;
+        self.record(get_key(url), &response)?;
+        Ok(response)
+    }
+
+    async fn get_document(&self, url: &str) -> Result<Document, Error> {
+        let document = self.inner.get_document(url).await?;
+        let response =
+            serde_json::to_value(&document).map_err(|e| Error::ParseError(e.to_string()))?;
+        self.record(document_key(url), &response)?;
+        Ok(document)
+    }
+
+    fn endpoint(&self) -> String {
+        self.inner.endpoint()
+    }
+}
+
+/// Serves the interactions of a cassette back in the order they were recorded.
+/// Requests that were never recorded, or made more often than recorded, fail with
+/// [`Error::UnmatchedRequest`]
+pub struct ReplayTransport {
+    interactions: Mutex<HashMap<String, VecDeque<Value>>>,
+    /// Keeps serving the last response of a key once its recordings run out
+    repeat_last: bool,
+}
+
+impl ReplayTransport {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path).map_err(|e| Error::FetchError(e.to_string()))?;
+        let mut interactions: HashMap<String, VecDeque<Value>> = HashMap::new();
+
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| Error::FetchError(e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let interaction = serde_json::from_str::<Interaction>(&line)
+                .map_err(|e| Error::ParseError(e.to_string()))?;
+
+            interactions
+                .entry(interaction.key)
+                .or_default()
+                .push_back(interaction.response);
+        }
+
+        Ok(Self {
+            interactions: Mutex::new(interactions),
+            repeat_last: false,
+        })
+    }
+
+    /// Keeps serving the last recorded response of a request once its recordings run out,
+    /// e.g. for code that polls
+    pub fn with_repeat_last(mut self, repeat_last: bool) -> Self {
+        self.repeat_last = repeat_last;
+        self
+    }
+
+    fn next_response(&self, key: &str) -> Result<Value, Error> {
+        let mut interactions = self.interactions.lock().unwrap();
+
+        match interactions.get_mut(key) {
+            Some(responses) if responses.len() > 1 || !self.repeat_last => responses
+                .pop_front()
+                .ok_or_else(|| Error::UnmatchedRequest(key.to_string())),
+            Some(responses) if !responses.is_empty() => Ok(responses[0].clone()),
+            _ => Err(Error::UnmatchedRequest(key.to_string())),
+        }
+    }
+
+    fn replay_rpc(&self, request: &Value) -> Result<Value, Error> {
+        let mut response = self.next_response(&rpc_key(request))?;
+
+        // Ids are random on every run, hand back the one the caller expects
+        response["id"] = request["id"].clone();
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn send(&self, request: &Value) -> Result<Value, Error> {
+        self.replay_rpc(request)
+    }
+
+    async fn send_batch(&self, requests: &[Value]) -> Result<Value, Error> {
+        let responses = requests
+            .iter()
+            .map(|request| self.replay_rpc(request))
+            .collect::<Result<Vec<Value>, Error>>()?;
+
+        Ok(json!(responses))
+    }
+
+    async fn get_json(&self, url: &str) -> Result<Value, Error> {
+        self.next_response(&get_key(url))
+    }
+
+    async fn get_document(&self, url: &str) -> Result<Document, Error> {
+        let response = self.next_response(&document_key(url))?;
+        serde_json::from_value(response).map_err(|e| Error::ParseError(e.to_string()))
+    }
+
+    fn endpoint(&self) -> String {
+        "replay".to_string()
+    }
+}
+
+fn rpc_key(request: &Value) -> String {
+    request_key(
+        request["method"].as_str().unwrap_or_default(),
+        &request["params"],
+    )
+}
+
+fn get_key(url: &str) -> String {
+    format!("GET {}", url)
+}
+
+fn document_key(url: &str) -> String {
+    format!("GET document {}", url)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::client::{transport::MemoryTransport, SolanaMirrorRpcClient};
+
+    const OWNER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const QUOTE_URL: &str =
+        "https://quote-api.jup.ag/v6/quote?inputMint=So11111111111111111111111111111111111111112";
+
+    #[tokio::test]
+    async fn replays_a_recorded_cassette() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.jsonl", uuid::Uuid::new_v4()));
+        let owner = OWNER.parse().unwrap();
+
+        let memory = MemoryTransport::new().with_response(
+            "getBalance",
+            json!([OWNER, null]),
+            json!({ "context": { "apiVersion": "2.0.0", "slot": 1 }, "value": 42 }),
+        );
+        memory.insert_json(QUOTE_URL, json!({ "outAmount": "150000000" }));
+
+        let recorder = RecordingTransport::new(Arc::new(memory), &path).unwrap();
+        let client = SolanaMirrorRpcClient::with_transport(Arc::new(recorder));
+        assert_eq!(client.get_balance(&owner, None).await.unwrap(), 42);
+        assert_eq!(client.get_balance(&owner, None).await.unwrap(), 42);
+        assert_eq!(
+            client.get_json(QUOTE_URL).await.unwrap()["outAmount"],
+            "150000000"
+        );
+
+        let replay = ReplayTransport::new(&path).unwrap();
+        let client = SolanaMirrorRpcClient::with_transport(Arc::new(replay));
+        assert_eq!(client.get_balance(&owner, None).await.unwrap(), 42);
+        assert_eq!(client.get_balance(&owner, None).await.unwrap(), 42);
+        assert_eq!(
+            client.get_json(QUOTE_URL).await.unwrap()["outAmount"],
+            "150000000"
+        );
+
+        // Only two balances were recorded, a third call must not get stale data
+        assert!(matches!(
+            client.get_balance(&owner, None).await,
+            Err(Error::UnmatchedRequest(_))
+        ));
+        assert!(matches!(
+            client.get_json("https://example.com").await,
+            Err(Error::UnmatchedRequest(_))
+        ));
+
+        let replay = ReplayTransport::new(&path).unwrap().with_repeat_last(true);
+        let client = SolanaMirrorRpcClient::with_transport(Arc::new(replay));
+        for _ in 0..3 {
+            assert_eq!(client.get_balance(&owner, None).await.unwrap(), 42);
+        }
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
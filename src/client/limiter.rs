@@ -0,0 +1,159 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Request budget for a single endpoint
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    /// Sustained requests per second the bucket refills at. Infinite, zero, negative and NaN
+    /// rates don't throttle at all
+    pub requests_per_second: f64,
+    /// Requests that can go out back to back before throttling kicks in
+    pub burst: u32,
+    /// Requests allowed in flight at the same time
+    pub max_in_flight: usize,
+}
+
+impl RateLimit {
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        Self {
+            requests_per_second,
+            burst: burst.max(1),
+            max_in_flight: Semaphore::MAX_PERMITS,
+        }
+    }
+
+    /// Only caps concurrency, without throttling the request rate
+    pub fn concurrency(max_in_flight: usize) -> Self {
+        Self {
+            requests_per_second: f64::INFINITY,
+            burst: 1,
+            max_in_flight,
+        }
+    }
+
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight;
+        self
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Token bucket paired with a max-in-flight semaphore
+struct Budget {
+    limit: RateLimit,
+    bucket: Mutex<Bucket>,
+    in_flight: Arc<Semaphore>,
+}
+
+impl Budget {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            bucket: Mutex::new(Bucket {
+                tokens: limit.burst as f64,
+                refilled_at: Instant::now(),
+            }),
+            in_flight: Arc::new(Semaphore::new(
+                limit.max_in_flight.clamp(1, Semaphore::MAX_PERMITS),
+            )),
+            limit,
+        }
+    }
+
+    async fn acquire(&self) -> Permit {
+        let permit = self
+            .in_flight
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("Rate limiter semaphore is never closed");
+
+        while let Some(wait) = self.take_token() {
+            tokio::time::sleep(wait).await;
+        }
+
+        Permit {
+            _in_flight: Some(permit),
+        }
+    }
+
+    /// Takes a token if one is available, otherwise returns how long until the next one
+    fn take_token(&self) -> Option<Duration> {
+        let rate = self.limit.requests_per_second;
+        if !rate.is_finite() || rate <= 0.0 {
+            return None;
+        }
+
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(self.limit.burst as f64);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+/// Held for the duration of a request, frees its in-flight slot on drop
+pub struct Permit {
+    _in_flight: Option<OwnedSemaphorePermit>,
+}
+
+/// Hands out request permits with a separate budget per endpoint.
+/// Endpoints without a configured limit are not throttled
+#[derive(Default)]
+pub struct Governor {
+    default_limit: Option<RateLimit>,
+    endpoint_limits: HashMap<String, RateLimit>,
+    budgets: Mutex<HashMap<String, Arc<Budget>>>,
+}
+
+impl Governor {
+    /// Sets the limit applied to every endpoint without its own
+    pub fn set_default_limit(&mut self, limit: RateLimit) {
+        self.default_limit = Some(limit);
+        self.budgets.lock().unwrap().clear();
+    }
+
+    /// Sets the limit for a single endpoint
+    pub fn set_endpoint_limit(&mut self, endpoint: &str, limit: RateLimit) {
+        self.endpoint_limits.insert(endpoint.to_string(), limit);
+        self.budgets.lock().unwrap().remove(endpoint);
+    }
+
+    /// Waits until the endpoint's budget allows one more request
+    pub async fn acquire(&self, endpoint: &str) -> Permit {
+        match self.budget(endpoint) {
+            Some(budget) => budget.acquire().await,
+            None => Permit { _in_flight: None },
+        }
+    }
+
+    fn budget(&self, endpoint: &str) -> Option<Arc<Budget>> {
+        let limit = self
+            .endpoint_limits
+            .get(endpoint)
+            .or(self.default_limit.as_ref())?;
+
+        let mut budgets = self.budgets.lock().unwrap();
+        let budget = budgets
+            .entry(endpoint.to_string())
+            .or_insert_with(|| Arc::new(Budget::new(limit.clone())));
+
+        Some(budget.clone())
+    }
+}
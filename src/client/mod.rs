@@ -1,21 +1,37 @@
-use std::pin::Pin;
-use std::{future::Future, sync::Arc};
+use std::fmt;
+use std::{collections::HashMap, sync::Arc};
 
-use crate::enums::Error;
+use crate::enums::{Error, RpcError};
+use crate::utils::create_batches;
 use base64::Engine;
+use futures::future::join_all;
+use limiter::{Governor, RateLimit};
 use reqwest::Client;
+use retry::{retry, RetryPolicy};
 use serde::{self, de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{from_value, Value};
 use solana_sdk::pubkey::Pubkey;
+use transport::{Document, HttpTransport, Transport};
 use types::{
-    AccountDataResultData, AccountsResultData, BalanceResultData, DecimalsResultData, Signature,
-    Transaction,
+    AccountDataResultData, AccountsResultData, BalanceResultData, DecimalsResultData, EpochInfo,
+    LatestBlockhashResultData, MultipleAccountsResultData, ProgramAccountsResultData, Signature,
+    TokenLargestAccountsResultData, Transaction, UiAccount,
 };
 use uuid::Uuid;
 
+pub mod cassette;
+pub mod limiter;
+pub mod pool;
+pub mod pubsub;
+pub mod retry;
+pub mod transport;
 pub mod types;
 
+/// Batch size used unless the client is configured otherwise
+const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+
 #[derive(Serialize, Deserialize)]
+#[allow(clippy::enum_variant_names)]
 pub enum JsonRpcMethod {
     GetTokenAccountsByOwner,
     GetBalance,
@@ -23,18 +39,31 @@ pub enum JsonRpcMethod {
     GetDecimals,
     GetTransaction,
     GetSignaturesForAddress,
+    GetSlot,
+    GetMultipleAccounts,
+    GetProgramAccounts,
+    GetTokenLargestAccounts,
+    GetEpochInfo,
+    GetLatestBlockhash,
 }
 
-impl JsonRpcMethod {
-    pub fn to_string(&self) -> String {
-        match self {
-            JsonRpcMethod::GetTokenAccountsByOwner => "getTokenAccountsByOwner".to_string(),
-            JsonRpcMethod::GetBalance => "getBalance".to_string(),
-            JsonRpcMethod::GetAccountInfo => "getAccountInfo".to_string(),
-            JsonRpcMethod::GetDecimals => "getTokenSupply".to_string(),
-            JsonRpcMethod::GetTransaction => "getTransaction".to_string(),
-            JsonRpcMethod::GetSignaturesForAddress => "getSignaturesForAddress".to_string(),
-        }
+impl fmt::Display for JsonRpcMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let method = match self {
+            JsonRpcMethod::GetTokenAccountsByOwner => "getTokenAccountsByOwner",
+            JsonRpcMethod::GetBalance => "getBalance",
+            JsonRpcMethod::GetAccountInfo => "getAccountInfo",
+            JsonRpcMethod::GetDecimals => "getTokenSupply",
+            JsonRpcMethod::GetTransaction => "getTransaction",
+            JsonRpcMethod::GetSignaturesForAddress => "getSignaturesForAddress",
+            JsonRpcMethod::GetSlot => "getSlot",
+            JsonRpcMethod::GetMultipleAccounts => "getMultipleAccounts",
+            JsonRpcMethod::GetProgramAccounts => "getProgramAccounts",
+            JsonRpcMethod::GetTokenLargestAccounts => "getTokenLargestAccounts",
+            JsonRpcMethod::GetEpochInfo => "getEpochInfo",
+            JsonRpcMethod::GetLatestBlockhash => "getLatestBlockhash",
+        };
+        write!(f, "{}", method)
     }
 }
 
@@ -62,8 +91,9 @@ pub struct JsonRpcError {
 
 #[derive(Serialize, Deserialize, Debug)]
 struct ErrorDetails {
-    code: i32,
+    code: i64,
     message: String,
+    data: Option<Value>,
 }
 
 // get_token_accounts_by_owner
@@ -120,6 +150,107 @@ pub struct GetAccountDataConfig {
 
 pub type GetAccountDataParams = (String, Option<GetAccountDataConfig>);
 
+// get_multiple_accounts
+pub type GetMultipleAccountsResponse = JsonRpcResponse<MultipleAccountsResultData>;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GetMultipleAccountsConfig {
+    pub commitment: Option<String>,
+    pub encoding: Option<String>,
+    #[serde(rename = "dataSlice")]
+    pub data_slice: Option<DataSlice>,
+    #[serde(rename = "minContextSlot")]
+    pub min_context_slot: Option<u64>,
+}
+
+pub type GetMultipleAccountsParams = (Vec<String>, Option<GetMultipleAccountsConfig>);
+
+/// Most accounts a node accepts in a single `getMultipleAccounts` call
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+// get_program_accounts
+pub type GetProgramAccountsResponse = JsonRpcResponse<ProgramAccountsResultData>;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct GetProgramAccountsConfig {
+    pub commitment: Option<String>,
+    pub encoding: Option<String>,
+    #[serde(rename = "dataSlice")]
+    pub data_slice: Option<DataSlice>,
+    pub filters: Option<Vec<RpcFilter>>,
+    #[serde(rename = "withContext")]
+    pub with_context: Option<bool>,
+    #[serde(rename = "minContextSlot")]
+    pub min_context_slot: Option<u64>,
+}
+
+impl GetProgramAccountsConfig {
+    /// Adds a filter, accounts must match every filter to be returned
+    pub fn with_filter(mut self, filter: RpcFilter) -> Self {
+        self.filters.get_or_insert_with(Vec::new).push(filter);
+        self
+    }
+
+    pub fn with_encoding(mut self, encoding: &str) -> Self {
+        self.encoding = Some(encoding.to_string());
+        self
+    }
+
+    /// Only returns `length` bytes of data starting at `offset`
+    pub fn with_data_slice(mut self, offset: usize, length: usize) -> Self {
+        self.data_slice = Some(DataSlice { length, offset });
+        self
+    }
+
+    /// Also returns the slot the accounts were read at
+    pub fn with_context(mut self) -> Self {
+        self.with_context = Some(true);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum RpcFilter {
+    #[serde(rename = "dataSize")]
+    DataSize(u64),
+    #[serde(rename = "memcmp")]
+    Memcmp(Memcmp),
+}
+
+impl RpcFilter {
+    /// Matches accounts whose data is exactly `size` bytes long
+    pub fn data_size(size: u64) -> Self {
+        RpcFilter::DataSize(size)
+    }
+
+    /// Matches accounts whose data contains `bytes` at `offset`
+    pub fn memcmp(offset: usize, bytes: &[u8]) -> Self {
+        RpcFilter::Memcmp(Memcmp {
+            offset,
+            bytes: base64::prelude::BASE64_STANDARD.encode(bytes),
+            encoding: Some("base64".to_string()),
+        })
+    }
+
+    /// Matches accounts that store `pubkey` at `offset`, e.g. an owner or authority field
+    pub fn memcmp_pubkey(offset: usize, pubkey: &Pubkey) -> Self {
+        RpcFilter::Memcmp(Memcmp {
+            offset,
+            bytes: pubkey.to_string(),
+            encoding: Some("base58".to_string()),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Memcmp {
+    pub offset: usize,
+    pub bytes: String,
+    pub encoding: Option<String>,
+}
+
+pub type GetProgramAccountsParams = (String, Option<GetProgramAccountsConfig>);
+
 // get_decimals
 pub type GetDecimalsResponse = JsonRpcResponse<DecimalsResultData>;
 
@@ -130,6 +261,40 @@ pub struct GetDecimalsConfig {
 
 pub type GetDecimalsParams = (String, Option<GetDecimalsConfig>);
 
+// get_token_largest_accounts
+pub type GetTokenLargestAccountsResponse = JsonRpcResponse<TokenLargestAccountsResultData>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetTokenLargestAccountsConfig {
+    pub commitment: Option<String>,
+}
+
+pub type GetTokenLargestAccountsParams = (String, Option<GetTokenLargestAccountsConfig>);
+
+// get_epoch_info
+pub type GetEpochInfoResponse = JsonRpcResponse<EpochInfo>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetEpochInfoConfig {
+    pub commitment: Option<String>,
+    #[serde(rename = "minContextSlot")]
+    pub min_context_slot: Option<u64>,
+}
+
+pub type GetEpochInfoParams = (Option<GetEpochInfoConfig>,);
+
+// get_latest_blockhash
+pub type GetLatestBlockhashResponse = JsonRpcResponse<LatestBlockhashResultData>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetLatestBlockhashConfig {
+    pub commitment: Option<String>,
+    #[serde(rename = "minContextSlot")]
+    pub min_context_slot: Option<u64>,
+}
+
+pub type GetLatestBlockhashParams = (Option<GetLatestBlockhashConfig>,);
+
 // get_signatures_for_address
 pub type GetSignaturesForAddressResponse = JsonRpcResponse<Vec<Signature>>;
 
@@ -156,38 +321,33 @@ pub struct GetTransactionConfig {
 
 pub type GetTransactionParams = (String, Option<GetTransactionConfig>);
 
-// TODO: implement on all methods
-#[allow(dead_code)]
-async fn retry<T, F>(callback: F, max_retries: u8) -> Result<T, Error>
-where
-    F: Fn() -> Pin<Box<dyn Future<Output = Result<T, Error>>>>,
-{
-    for attempt in 0..=max_retries {
-        match callback().await {
-            Ok(res) => return Ok(res),
-            Err(e) => {
-                if attempt == max_retries {
-                    return Err(e);
-                } else {
-                    match e {
-                        Error::TooManyRequests => continue,
-                        _ => return Err(e),
-                    }
-                }
-            }
-        }
+/// Extracts the error of a JSON-RPC response, if it carries one
+pub(crate) fn parse_rpc_error(res: &Value) -> Option<Error> {
+    let deserialized_err = from_value::<JsonRpcError>(res.clone()).ok()?;
+    let ErrorDetails {
+        code,
+        message,
+        data,
+    } = deserialized_err.error;
+
+    match code {
+        429 => Some(Error::TooManyRequests),
+        _ => Some(Error::RpcError(RpcError::new(code, message, data))),
     }
+}
 
-    Err(Error::FetchError("Fetch error occurred".to_string()))
+/// Host of the url, used to keep separate request budgets per HTTP API
+fn url_host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default()
 }
 
 fn deserialize<T: DeserializeOwned>(res: &Value) -> Result<T, Error> {
     // Try deserializing into an error first
-    if let Ok(deserialized_err) = from_value::<JsonRpcError>(res.clone()) {
-        match deserialized_err.error.code {
-            429 => return Err(Error::TooManyRequests),
-            _ => return Err(Error::FetchError("Fetch error occurred".to_string())),
-        }
+    if let Some(e) = parse_rpc_error(res) {
+        return Err(e);
     }
 
     match from_value::<T>(res.clone()) {
@@ -196,52 +356,159 @@ fn deserialize<T: DeserializeOwned>(res: &Value) -> Result<T, Error> {
     }
 }
 
+/// Matches the entries of a batch response to the requests by id, since servers may reorder
+/// them or leave some out. Every entry is deserialized on its own so one failed entry doesn't
+/// sink the rest. Errors for the batch as a whole are returned as is
+fn deserialize_batch<T: DeserializeOwned>(
+    res: &Value,
+    requests: &[Value],
+) -> Result<Vec<Result<T, Error>>, Error> {
+    let entries = match res {
+        Value::Array(entries) => entries,
+        _ => {
+            return Err(parse_rpc_error(res)
+                .unwrap_or_else(|| Error::ParseError("Expected a batch response".to_string())))
+        }
+    };
+
+    let mut by_id: HashMap<String, &Value> = entries
+        .iter()
+        .filter_map(|entry| entry["id"].as_str().map(|id| (id.to_string(), entry)))
+        .collect();
+
+    Ok(requests
+        .iter()
+        .map(|request| {
+            let id = request["id"].as_str().unwrap_or_default();
+            match by_id.remove(id) {
+                Some(entry) => deserialize::<T>(entry),
+                None => Err(Error::MissingResponse(id.to_string())),
+            }
+        })
+        .collect())
+}
+
 pub struct SolanaMirrorRpcClient {
-    inner_client: Arc<Client>,
-    pub rpc_url: String,
+    transport: Arc<dyn Transport>,
+    retry_policy: RetryPolicy,
+    governor: Governor,
+    max_batch_size: usize,
 }
 
 impl SolanaMirrorRpcClient {
     pub fn new(client: Arc<Client>, rpc_url: String) -> Self {
+        Self::with_transport(Arc::new(HttpTransport::new(client, rpc_url)))
+    }
+
+    /// Creates a client that sends every request through the given transport
+    pub fn with_transport(transport: Arc<dyn Transport>) -> Self {
         Self {
-            inner_client: client,
-            rpc_url,
+            transport,
+            retry_policy: RetryPolicy::default(),
+            governor: Governor::default(),
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
         }
     }
 
-    async fn make_batch_request<T: Serialize>(
+    /// URL of the node requests are sent to, or the name of the transport when it isn't HTTP
+    pub fn rpc_url(&self) -> String {
+        self.transport.endpoint()
+    }
+
+    /// Replaces the policy used to retry failed requests
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Caps how many requests go in a single batch, bigger batches are split
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+        self
+    }
+
+    /// Throttles every endpoint that has no limit of its own
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.governor.set_default_limit(limit);
+        self
+    }
+
+    /// Throttles a single endpoint, identified by its RPC URL or, for HTTP APIs, by its host
+    pub fn with_endpoint_rate_limit(mut self, endpoint: &str, limit: RateLimit) -> Self {
+        self.governor.set_endpoint_limit(endpoint, limit);
+        self
+    }
+
+    /// Sends the requests in batches of at most `max_batch_size` and returns one result per
+    /// request, in the same order. A batch that fails as a whole fails each of its entries.
+    /// Entries that fail with a retryable error or get no response are sent again, following
+    /// the retry policy
+    async fn make_batch_request<T: Serialize, R: DeserializeOwned>(
         &self,
-        body: &Vec<JsonRpcRequest<T>>,
-    ) -> Result<Value, Error> {
-        let serialized = match serde_json::to_string(body) {
-            Ok(serialized) => serialized,
-            Err(e) => return Err(Error::ParseError(e.to_string())),
-        };
+        body: &[JsonRpcRequest<T>],
+    ) -> Result<Vec<Result<R, Error>>, Error> {
+        let requests = body
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<Value>, _>>()
+            .map_err(|e| Error::ParseError(e.to_string()))?;
+
+        let endpoint = self.transport.endpoint();
+        let mut results: Vec<Option<Result<R, Error>>> = requests.iter().map(|_| None).collect();
+        let mut pending: Vec<usize> = (0..requests.len()).collect();
+        let mut attempt = 1;
+
+        loop {
+            for batch in create_batches(&pending, self.max_batch_size, None) {
+                let batch_requests: Vec<Value> =
+                    batch.iter().map(|idx| requests[*idx].clone()).collect();
+
+                let batch_results = async {
+                    let _permit = self.governor.acquire(&endpoint).await;
+                    let res = self.transport.send_batch(&batch_requests).await?;
+                    deserialize_batch::<R>(&res, &batch_requests)
+                }
+                .await;
+
+                match batch_results {
+                    Ok(batch_results) => {
+                        for (idx, result) in batch.into_iter().zip(batch_results) {
+                            results[idx] = Some(result);
+                        }
+                    }
+                    Err(e) => {
+                        for idx in batch {
+                            results[idx] = Some(Err(e.clone()));
+                        }
+                    }
+                }
+            }
+
+            pending.retain(|idx| matches!(&results[*idx], Some(Err(e)) if e.is_retryable()));
 
-        let req = self
-            .inner_client
-            .post(&self.rpc_url)
-            .header("Content-Type", "application/json")
-            .header("solana-client", "js/0.0.0-development")
-            .body(serialized);
-
-        match req.send().await {
-            Ok(response) => {
-                let res = response
-                    .json::<Value>()
-                    .await
-                    .map_err(|e| Error::ParseError(e.to_string()))?;
-                Ok(res)
+            if pending.is_empty() || attempt >= self.retry_policy.max_attempts {
+                break;
             }
-            Err(e) => Err(Error::FetchError(e.to_string())),
+
+            let retry_after = pending
+                .iter()
+                .filter_map(|idx| match &results[*idx] {
+                    Some(Err(e)) => e.retry_after(),
+                    _ => None,
+                })
+                .max();
+            tokio::time::sleep(self.retry_policy.delay(attempt - 1, retry_after)).await;
+            attempt += 1;
         }
+
+        Ok(results.into_iter().flatten().collect())
     }
 
-    async fn make_request<T: Serialize>(
+    async fn make_request<T: Serialize, R: DeserializeOwned>(
         &self,
         method: JsonRpcMethod,
         params: Option<T>,
-    ) -> Result<Value, Error> {
+    ) -> Result<R, Error> {
         let body = &JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
             method: method.to_string(),
@@ -249,28 +516,38 @@ impl SolanaMirrorRpcClient {
             id: Uuid::new_v4().to_string(),
         };
 
-        let serialized = match serde_json::to_string(body) {
-            Ok(serialized) => serialized,
-            Err(e) => return Err(Error::ParseError(e.to_string())),
-        };
+        let request = serde_json::to_value(body).map_err(|e| Error::ParseError(e.to_string()))?;
 
-        let req = self
-            .inner_client
-            .post(&self.rpc_url)
-            .header("Content-Type", "application/json")
-            .header("solana-client", "js/0.0.0-development")
-            .body(serialized);
-
-        match req.send().await {
-            Ok(response) => {
-                let res = response
-                    .json::<Value>()
-                    .await
-                    .map_err(|e| Error::ParseError(e.to_string()))?;
-                Ok(res)
-            }
-            Err(e) => Err(Error::FetchError(e.to_string())),
-        }
+        let endpoint = self.transport.endpoint();
+
+        retry(&self.retry_policy, || async {
+            let _permit = self.governor.acquire(&endpoint).await;
+            let res = self.transport.send(&request).await?;
+            deserialize::<R>(&res)
+        })
+        .await
+    }
+
+    /// Fetches a JSON document from an HTTP API through the client's transport
+    pub async fn get_json(&self, url: &str) -> Result<Value, Error> {
+        let host = url_host(url);
+
+        retry(&self.retry_policy, || async {
+            let _permit = self.governor.acquire(&host).await;
+            self.transport.get_json(url).await
+        })
+        .await
+    }
+
+    /// Fetches a url that may serve an image or JSON through the client's transport
+    pub async fn get_document(&self, url: &str) -> Result<Document, Error> {
+        let host = url_host(url);
+
+        retry(&self.retry_policy, || async {
+            let _permit = self.governor.acquire(&host).await;
+            self.transport.get_document(url).await
+        })
+        .await
     }
 
     pub async fn get_token_accounts_by_owner(
@@ -281,11 +558,8 @@ impl SolanaMirrorRpcClient {
     ) -> Result<GetTokenAccountsByOwnerResponse, Error> {
         let params: GetTokenAccountsByOwnerParams = (owner.to_string(), filter, config);
 
-        let res = self
-            .make_request(JsonRpcMethod::GetTokenAccountsByOwner, Some(params))
-            .await?;
-
-        deserialize::<GetTokenAccountsByOwnerResponse>(&res)
+        self.make_request(JsonRpcMethod::GetTokenAccountsByOwner, Some(params))
+            .await
     }
 
     pub async fn get_balance(
@@ -295,10 +569,10 @@ impl SolanaMirrorRpcClient {
     ) -> Result<u64, Error> {
         let params: GetBalanceParams = (owner.to_string(), config);
 
-        let res = self
-            .make_request(JsonRpcMethod::GetBalance, Some(params))
-            .await?;
-        match deserialize::<GetBalanceResponse>(&res) {
+        match self
+            .make_request::<_, GetBalanceResponse>(JsonRpcMethod::GetBalance, Some(params))
+            .await
+        {
             Ok(bal) => Ok(bal.result.unwrap().value),
             Err(e) => Err(e),
         }
@@ -311,10 +585,10 @@ impl SolanaMirrorRpcClient {
     ) -> Result<Vec<u8>, Error> {
         let params: GetAccountDataParams = (pubkey.to_string(), config);
 
-        let res = self
-            .make_request(JsonRpcMethod::GetAccountInfo, Some(params))
-            .await?;
-        match deserialize::<GetAccountDataResponse>(&res) {
+        match self
+            .make_request::<_, GetAccountDataResponse>(JsonRpcMethod::GetAccountInfo, Some(params))
+            .await
+        {
             Ok(acc) => {
                 let base64_data = &acc.result.value.data[0];
                 let decoded_data = base64::prelude::BASE64_STANDARD
@@ -327,6 +601,51 @@ impl SolanaMirrorRpcClient {
         }
     }
 
+    /// Fetches several accounts at once, splitting the keys into chunks of
+    /// [`MAX_MULTIPLE_ACCOUNTS`]. Missing accounts come back as None, in the same order as `pubkeys`
+    pub async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+        config: Option<GetMultipleAccountsConfig>,
+    ) -> Result<Vec<Option<UiAccount>>, Error> {
+        let chunk_futures = pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS).map(|chunk| {
+            let params: GetMultipleAccountsParams = (
+                chunk.iter().map(|pubkey| pubkey.to_string()).collect(),
+                config.clone(),
+            );
+
+            self.make_request::<_, GetMultipleAccountsResponse>(
+                JsonRpcMethod::GetMultipleAccounts,
+                Some(params),
+            )
+        });
+
+        let mut accounts = Vec::with_capacity(pubkeys.len());
+        for chunk in join_all(chunk_futures).await {
+            accounts.extend(chunk?.result.value);
+        }
+
+        Ok(accounts)
+    }
+
+    /// Returns the accounts owned by the program that match every filter of the config
+    pub async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        config: Option<GetProgramAccountsConfig>,
+    ) -> Result<ProgramAccountsResultData, Error> {
+        let params: GetProgramAccountsParams = (program_id.to_string(), config);
+
+        let res = self
+            .make_request::<_, GetProgramAccountsResponse>(
+                JsonRpcMethod::GetProgramAccounts,
+                Some(params),
+            )
+            .await?;
+
+        Ok(res.result)
+    }
+
     pub async fn get_decimals(
         &self,
         mint: &Pubkey,
@@ -334,10 +653,52 @@ impl SolanaMirrorRpcClient {
     ) -> Result<GetDecimalsResponse, Error> {
         let params: GetDecimalsParams = (mint.to_string(), config);
 
+        self.make_request(JsonRpcMethod::GetDecimals, Some(params))
+            .await
+    }
+
+    /// Returns the 20 largest token accounts of the mint
+    pub async fn get_token_largest_accounts(
+        &self,
+        mint: &Pubkey,
+        config: Option<GetTokenLargestAccountsConfig>,
+    ) -> Result<GetTokenLargestAccountsResponse, Error> {
+        let params: GetTokenLargestAccountsParams = (mint.to_string(), config);
+
+        self.make_request(JsonRpcMethod::GetTokenLargestAccounts, Some(params))
+            .await
+    }
+
+    /// Returns the current epoch and the position of the current slot within it
+    pub async fn get_epoch_info(
+        &self,
+        config: Option<GetEpochInfoConfig>,
+    ) -> Result<EpochInfo, Error> {
+        let params: GetEpochInfoParams = (config,);
+
         let res = self
-            .make_request(JsonRpcMethod::GetDecimals, Some(params))
+            .make_request::<_, GetEpochInfoResponse>(JsonRpcMethod::GetEpochInfo, Some(params))
             .await?;
-        deserialize::<GetDecimalsResponse>(&res)
+
+        Ok(res.result)
+    }
+
+    /// Returns a recent blockhash to build transactions with, and the last block height
+    /// at which it is still valid
+    pub async fn get_latest_blockhash(
+        &self,
+        config: Option<GetLatestBlockhashConfig>,
+    ) -> Result<LatestBlockhashResultData, Error> {
+        let params: GetLatestBlockhashParams = (config,);
+
+        let res = self
+            .make_request::<_, GetLatestBlockhashResponse>(
+                JsonRpcMethod::GetLatestBlockhash,
+                Some(params),
+            )
+            .await?;
+
+        Ok(res.result)
     }
 
     pub async fn get_signatures_for_address(
@@ -347,17 +708,15 @@ impl SolanaMirrorRpcClient {
     ) -> Result<GetSignaturesForAddressResponse, Error> {
         let params: GetSignaturesForAddressParams = (address.to_string(), config);
 
-        let res = self
-            .make_request(JsonRpcMethod::GetSignaturesForAddress, Some(params))
-            .await?;
-        deserialize::<GetSignaturesForAddressResponse>(&res)
+        self.make_request(JsonRpcMethod::GetSignaturesForAddress, Some(params))
+            .await
     }
 
     pub async fn get_transactions(
         &self,
         signatures: &[String],
         config: Option<GetTransactionConfig>,
-    ) -> Result<Vec<GetTransactionResponse>, Error> {
+    ) -> Result<Vec<Result<GetTransactionResponse, Error>>, Error> {
         let body: Vec<JsonRpcRequest<GetTransactionParams>> = signatures
             .iter()
             .map(|signature| {
@@ -371,7 +730,84 @@ impl SolanaMirrorRpcClient {
             })
             .collect();
 
-        let res = self.make_batch_request(&body).await?;
-        deserialize::<Vec<GetTransactionResponse>>(&res)
+        self.make_batch_request(&body).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    use super::*;
+
+    const BAD_SIGNATURE: &str = "bad";
+
+    /// Answers every batch with empty transactions, except batches asking for the bad
+    /// signature, which fail as a whole
+    #[derive(Default)]
+    struct FlakyTransport {
+        bad_batches: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Transport for FlakyTransport {
+        async fn send(&self, _request: &Value) -> Result<Value, Error> {
+            Err(Error::FetchError("Only batches are served".to_string()))
+        }
+
+        async fn send_batch(&self, requests: &[Value]) -> Result<Value, Error> {
+            if requests
+                .iter()
+                .any(|request| request["params"][0] == BAD_SIGNATURE)
+            {
+                self.bad_batches.fetch_add(1, Ordering::Relaxed);
+                return Err(Error::TransportError("connection reset".to_string()));
+            }
+
+            Ok(Value::Array(
+                requests
+                    .iter()
+                    .map(|request| json!({ "jsonrpc": "2.0", "result": null, "id": request["id"] }))
+                    .collect(),
+            ))
+        }
+
+        async fn get_json(&self, url: &str) -> Result<Value, Error> {
+            Err(Error::FetchError(format!("No document for {}", url)))
+        }
+
+        async fn get_document(&self, url: &str) -> Result<Document, Error> {
+            Err(Error::FetchError(format!("No document for {}", url)))
+        }
+
+        fn endpoint(&self) -> String {
+            "flaky".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_batches_fail_their_entries_only() {
+        let transport = Arc::new(FlakyTransport::default());
+        let client = SolanaMirrorRpcClient::with_transport(transport.clone())
+            .with_max_batch_size(1)
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 3,
+                base_delay: Duration::from_millis(1),
+                ..RetryPolicy::default()
+            });
+
+        let signatures = vec!["good".to_string(), BAD_SIGNATURE.to_string()];
+        let results = client.get_transactions(&signatures, None).await.unwrap();
+
+        assert!(matches!(&results[0], Ok(response) if response.result.is_none()));
+        assert!(matches!(&results[1], Err(Error::TransportError(_))));
+        // One retry layer, so the failing batch goes out once per attempt
+        assert_eq!(transport.bad_batches.load(Ordering::Relaxed), 3);
     }
 }
@@ -0,0 +1,406 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::{
+    limiter::{Governor, RateLimit},
+    parse_rpc_error,
+    transport::{Document, HttpTransport, Transport},
+    JsonRpcMethod,
+};
+use crate::enums::Error;
+
+/// Weight of the latest sample in the latency and error rate moving averages
+const EWMA_ALPHA: f64 = 0.2;
+/// How long a rate limited endpoint is skipped when it didn't send a `Retry-After`
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(5);
+/// Connection and request timeouts of the pool's HTTP client, so a hung provider fails over
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a `getSlot` may take before the endpoint's lag is considered unknown
+const SLOT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Score of an endpoint whose slot couldn't be read, as bad as one failing every request
+const UNKNOWN_LAG_PENALTY: f64 = 1000.0;
+
+/// Health snapshot of a pool member
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EndpointStats {
+    pub endpoint: String,
+    pub requests: u64,
+    pub errors: u64,
+    #[serde(rename = "rateLimited")]
+    pub rate_limited: u64,
+    /// Moving average of the response time
+    #[serde(rename = "latencyMs")]
+    pub latency_ms: Option<f64>,
+    /// Moving average of the share of failed requests, between 0 and 1
+    #[serde(rename = "errorRate")]
+    pub error_rate: f64,
+    /// Last slot reported by `getSlot`
+    pub slot: Option<u64>,
+    /// Slots behind the most advanced endpoint of the pool. None until measured, or when
+    /// the last `getSlot` failed
+    #[serde(rename = "slotLag")]
+    pub slot_lag: Option<u64>,
+    /// Whether the endpoint is skipped because it recently rate limited us
+    #[serde(rename = "coolingDown")]
+    pub cooling_down: bool,
+}
+
+struct PoolEndpoint {
+    transport: Arc<dyn Transport>,
+    stats: Mutex<EndpointStats>,
+    cooldown_until: Mutex<Option<Instant>>,
+}
+
+impl PoolEndpoint {
+    fn record_success(&self, latency: Duration) {
+        let mut stats = self.stats.lock().unwrap();
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+
+        stats.requests += 1;
+        stats.latency_ms = Some(match stats.latency_ms {
+            Some(avg) => avg + EWMA_ALPHA * (latency_ms - avg),
+            None => latency_ms,
+        });
+        stats.error_rate -= EWMA_ALPHA * stats.error_rate;
+    }
+
+    fn record_failure(&self, error: &Error) {
+        let mut stats = self.stats.lock().unwrap();
+
+        stats.requests += 1;
+        stats.errors += 1;
+        stats.error_rate += EWMA_ALPHA * (1.0 - stats.error_rate);
+
+        if is_rate_limit(error) {
+            stats.rate_limited += 1;
+            let cooldown = error.retry_after().unwrap_or(DEFAULT_COOLDOWN);
+            *self.cooldown_until.lock().unwrap() = Some(Instant::now() + cooldown);
+        }
+    }
+
+    fn cooling_down(&self) -> bool {
+        matches!(*self.cooldown_until.lock().unwrap(), Some(until) if until > Instant::now())
+    }
+
+    /// Lower is better. Roughly milliseconds: failures and lag weigh as much as a slow node,
+    /// and an unknown lag counts as unhealthy
+    fn score(&self) -> f64 {
+        let stats = self.stats.lock().unwrap();
+
+        stats.latency_ms.unwrap_or(0.0)
+            + stats.error_rate * 1000.0
+            + stats
+                .slot_lag
+                .map(|slot_lag| slot_lag as f64 * 20.0)
+                .unwrap_or(UNKNOWN_LAG_PENALTY)
+    }
+}
+
+/// Spreads requests over several RPC providers, routing by health and failing over
+/// on errors or rate limits. It is a [`Transport`], so it plugs in wherever a single
+/// endpoint does
+pub struct EndpointPool {
+    // Shared with the background slot refreshes
+    endpoints: Arc<Vec<PoolEndpoint>>,
+    governor: Arc<Governor>,
+    slot_refresh_interval: Duration,
+    slots_refreshed_at: Arc<tokio::sync::Mutex<Option<Instant>>>,
+}
+
+impl EndpointPool {
+    /// Creates a pool of HTTP endpoints sharing one connection pool
+    pub fn new(rpc_urls: Vec<String>) -> Self {
+        let http_client = Arc::new(
+            Client::builder()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        );
+
+        Self::from_transports(
+            rpc_urls
+                .into_iter()
+                .map(|url| {
+                    Arc::new(HttpTransport::new(http_client.clone(), url)) as Arc<dyn Transport>
+                })
+                .collect(),
+        )
+    }
+
+    pub fn from_transports(transports: Vec<Arc<dyn Transport>>) -> Self {
+        let endpoints = transports
+            .into_iter()
+            .map(|transport| PoolEndpoint {
+                stats: Mutex::new(EndpointStats {
+                    endpoint: transport.endpoint(),
+                    ..EndpointStats::default()
+                }),
+                transport,
+                cooldown_until: Mutex::new(None),
+            })
+            .collect();
+
+        Self {
+            endpoints: Arc::new(endpoints),
+            governor: Arc::new(Governor::default()),
+            slot_refresh_interval: Duration::from_secs(30),
+            slots_refreshed_at: Arc::new(tokio::sync::Mutex::new(None)),
+        }
+    }
+
+    /// Gives a pool member its own request budget
+    pub fn with_endpoint_rate_limit(mut self, endpoint: &str, limit: RateLimit) -> Self {
+        Arc::get_mut(&mut self.governor)
+            .expect("the pool is configured before it routes requests")
+            .set_endpoint_limit(endpoint, limit);
+        self
+    }
+
+    /// How often slot lag is measured while routing requests
+    pub fn with_slot_refresh_interval(mut self, interval: Duration) -> Self {
+        self.slot_refresh_interval = interval;
+        self
+    }
+
+    /// Returns the health of every endpoint in the pool
+    pub fn stats(&self) -> Vec<EndpointStats> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| EndpointStats {
+                cooling_down: endpoint.cooling_down(),
+                ..endpoint.stats.lock().unwrap().clone()
+            })
+            .collect()
+    }
+
+    /// Asks every endpoint for its slot and updates how far behind each one is.
+    /// These calls are not counted in the request and error stats
+    pub async fn refresh_slots(&self) {
+        refresh_slots(&self.endpoints, &self.governor).await;
+    }
+
+    /// Refreshes the slots in a background task when they are stale, so no request waits
+    /// for it. Requests arriving while a refresh is running don't start another one
+    fn refresh_slots_if_stale(&self) {
+        if self.endpoints.len() < 2 {
+            return;
+        }
+
+        let mut refreshed_at = match self.slots_refreshed_at.clone().try_lock_owned() {
+            Ok(refreshed_at) => refreshed_at,
+            Err(_) => return,
+        };
+
+        let stale = match *refreshed_at {
+            Some(at) => at.elapsed() >= self.slot_refresh_interval,
+            None => true,
+        };
+
+        if stale {
+            let endpoints = self.endpoints.clone();
+            let governor = self.governor.clone();
+            tokio::spawn(async move {
+                refresh_slots(&endpoints, &governor).await;
+                *refreshed_at = Some(Instant::now());
+            });
+        }
+    }
+
+    /// Endpoint indexes from healthiest to least healthy, cooling down ones last
+    fn ranked(&self) -> Vec<usize> {
+        let mut ranked: Vec<(bool, f64, usize)> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(idx, endpoint)| (endpoint.cooling_down(), endpoint.score(), idx))
+            .collect();
+
+        ranked.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        ranked.into_iter().map(|(_, _, idx)| idx).collect()
+    }
+
+    async fn route<'a, T, F, Fut>(&'a self, send: F) -> Result<T, Error>
+    where
+        T: Routed,
+        F: Fn(&'a PoolEndpoint) -> Fut,
+        Fut: std::future::Future<Output = Result<T, Error>>,
+    {
+        self.refresh_slots_if_stale();
+
+        let mut last_error = Error::FetchError("The endpoint pool is empty".to_string());
+
+        for idx in self.ranked() {
+            let endpoint = &self.endpoints[idx];
+            let _permit = self.governor.acquire(&endpoint.transport.endpoint()).await;
+            let started_at = Instant::now();
+
+            let error = match send(endpoint).await {
+                // Rate limits and lagging nodes reported inside a JSON-RPC body also count as failures
+                Ok(res) => match res.rpc_error() {
+                    Some(e) if e.is_retryable() => e,
+                    _ => {
+                        // A batch is answered even when some entries failed, the client retries
+                        // those, but they still count against the endpoint
+                        match res.batch_errors().into_iter().find(Error::is_retryable) {
+                            Some(e) => endpoint.record_failure(&e),
+                            None => endpoint.record_success(started_at.elapsed()),
+                        }
+                        return Ok(res);
+                    }
+                },
+                Err(e) => e,
+            };
+
+            endpoint.record_failure(&error);
+            if !fails_over(&error) {
+                return Err(error);
+            }
+            last_error = error;
+        }
+
+        Err(last_error)
+    }
+}
+
+#[async_trait]
+impl Transport for EndpointPool {
+    async fn send(&self, request: &Value) -> Result<Value, Error> {
+        self.route(|endpoint| endpoint.transport.send(request))
+            .await
+    }
+
+    async fn send_batch(&self, requests: &[Value]) -> Result<Value, Error> {
+        self.route(|endpoint| endpoint.transport.send_batch(requests))
+            .await
+    }
+
+    async fn get_json(&self, url: &str) -> Result<Value, Error> {
+        // Plain HTTP APIs don't depend on the RPC provider, but going through the healthiest
+        // member still fails over when one transport can't reach them
+        self.route(|endpoint| endpoint.transport.get_json(url))
+            .await
+    }
+
+    async fn get_document(&self, url: &str) -> Result<Document, Error> {
+        self.route(|endpoint| endpoint.transport.get_document(url))
+            .await
+    }
+
+    fn endpoint(&self) -> String {
+        "pool".to_string()
+    }
+}
+
+/// Backs [`EndpointPool::refresh_slots`], also run in the background while routing
+async fn refresh_slots(endpoints: &[PoolEndpoint], governor: &Governor) {
+    let slot_futures = endpoints.iter().map(|endpoint| async move {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": JsonRpcMethod::GetSlot.to_string(),
+            "id": Uuid::new_v4().to_string(),
+        });
+
+        let _permit = governor.acquire(&endpoint.transport.endpoint()).await;
+
+        match tokio::time::timeout(SLOT_TIMEOUT, endpoint.transport.send(&request)).await {
+            Ok(Ok(res)) => res["result"].as_u64(),
+            _ => None,
+        }
+    });
+
+    let slots = join_all(slot_futures).await;
+    let max_slot = slots.iter().flatten().max().copied();
+
+    for (endpoint, slot) in endpoints.iter().zip(slots) {
+        let mut stats = endpoint.stats.lock().unwrap();
+        stats.slot = slot;
+        stats.slot_lag = match (slot, max_slot) {
+            (Some(slot), Some(max_slot)) => Some(max_slot - slot),
+            _ => None,
+        };
+    }
+}
+
+/// Responses the pool routes, which may carry an error in their body
+trait Routed {
+    fn rpc_error(&self) -> Option<Error>;
+
+    /// Errors of the entries of a batch response
+    fn batch_errors(&self) -> Vec<Error>;
+}
+
+impl Routed for Value {
+    fn rpc_error(&self) -> Option<Error> {
+        parse_rpc_error(self)
+    }
+
+    fn batch_errors(&self) -> Vec<Error> {
+        match self.as_array() {
+            Some(entries) => entries.iter().filter_map(parse_rpc_error).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Routed for Document {
+    fn rpc_error(&self) -> Option<Error> {
+        None
+    }
+
+    fn batch_errors(&self) -> Vec<Error> {
+        Vec::new()
+    }
+}
+
+/// Whether the next endpoint should be tried. Only errors the node answered in the
+/// JSON-RPC body are returned as is, anything wrong with the endpoint itself fails over
+fn fails_over(error: &Error) -> bool {
+    error.is_retryable() || error.is_transport_error() || matches!(error, Error::ParseError(_))
+}
+
+fn is_rate_limit(error: &Error) -> bool {
+    match error {
+        Error::TooManyRequests => true,
+        Error::HttpError { status, .. } => *status == 429,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::transport::MemoryTransport;
+
+    #[tokio::test]
+    async fn fails_over_when_an_endpoint_is_broken() {
+        // The first endpoint fails getHealth like a provider answering with an error page.
+        // Both report the same slot, so it is ranked first whether or not the background
+        // slot refresh ran
+        let broken = MemoryTransport::new().with_response("getSlot", json!(null), json!(42));
+        let healthy = MemoryTransport::new()
+            .with_response("getSlot", json!(null), json!(42))
+            .with_response("getHealth", json!(null), json!("ok"));
+        let pool = EndpointPool::from_transports(vec![Arc::new(broken), Arc::new(healthy)]);
+
+        let res = pool
+            .send(&json!({ "jsonrpc": "2.0", "method": "getHealth", "id": "1" }))
+            .await
+            .unwrap();
+        assert_eq!(res["result"], "ok");
+
+        let stats = pool.stats();
+        assert_eq!(stats[0].errors, 1);
+        assert_eq!(stats[1].errors, 0);
+    }
+}
@@ -0,0 +1,628 @@
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{
+    channel::mpsc::{unbounded as stream_channel, UnboundedReceiver, UnboundedSender},
+    SinkExt, Stream, StreamExt,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use tokio::{
+    net::TcpStream,
+    sync::mpsc::{
+        unbounded_channel, UnboundedReceiver as CommandReceiver, UnboundedSender as CommandSender,
+    },
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use super::{
+    parse_rpc_error,
+    retry::RetryPolicy,
+    types::{AccountNotification, LogsNotification, SignatureNotification, SlotInfo},
+};
+use crate::enums::Error;
+
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// How often a ping is sent to keep idle connections open
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+/// How long a connection has to stay open, without a confirmed subscription, before
+/// the reconnect backoff starts over
+const STABLE_CONNECTION: Duration = Duration::from_secs(30);
+
+enum Command {
+    Subscribe(u64, Subscription),
+    Unsubscribe(u64),
+}
+
+struct Subscription {
+    method: &'static str,
+    unsubscribe_method: &'static str,
+    params: Value,
+    sender: UnboundedSender<Result<Value, Error>>,
+    /// The node drops these after the first notification, so they are never resubscribed
+    one_shot: bool,
+    server_id: Option<u64>,
+}
+
+/// Stream of notifications for a single subscription. Dropping it unsubscribes
+pub struct SubscriptionStream<T> {
+    id: u64,
+    receiver: UnboundedReceiver<Result<Value, Error>>,
+    commands: CommandSender<Command>,
+    _notification: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Stream for SubscriptionStream<T> {
+    type Item = Result<T, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx).map(|item| {
+            item.map(|notification| {
+                notification.and_then(|value| {
+                    serde_json::from_value::<T>(value).map_err(|e| Error::ParseError(e.to_string()))
+                })
+            })
+        })
+    }
+}
+
+impl<T> Drop for SubscriptionStream<T> {
+    fn drop(&mut self) {
+        let _ = self.commands.send(Command::Unsubscribe(self.id));
+    }
+}
+
+/// WebSocket client for the Solana pubsub API. Keeps a single connection in the
+/// background, reconnecting and resubscribing whenever it drops
+pub struct SolanaMirrorPubsubClient {
+    commands: CommandSender<Command>,
+    next_id: AtomicU64,
+}
+
+impl SolanaMirrorPubsubClient {
+    /// Connects to the given WebSocket URL. Must be called from within a Tokio runtime
+    pub fn new(ws_url: String) -> Self {
+        Self::with_reconnect_policy(
+            ws_url,
+            RetryPolicy {
+                max_attempts: 10,
+                base_delay: Duration::from_millis(500),
+                max_delay: Duration::from_secs(30),
+                ..RetryPolicy::default()
+            },
+        )
+    }
+
+    /// Connects with a custom backoff between reconnects. Once `max_attempts` consecutive
+    /// connects fail, or drop before a subscription is confirmed, every stream ends
+    pub fn with_reconnect_policy(ws_url: String, reconnect_policy: RetryPolicy) -> Self {
+        let (commands, command_receiver) = unbounded_channel();
+        tokio::spawn(run(ws_url, command_receiver, reconnect_policy));
+
+        Self {
+            commands,
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Notifies every change to the lamports or data of an account
+    pub fn account_subscribe(
+        &self,
+        pubkey: &Pubkey,
+        commitment: Option<String>,
+    ) -> Result<SubscriptionStream<AccountNotification>, Error> {
+        self.subscribe(
+            "accountSubscribe",
+            "accountUnsubscribe",
+            json!([pubkey.to_string(), { "encoding": "jsonParsed", "commitment": commitment }]),
+            false,
+        )
+    }
+
+    /// Notifies every transaction that mentions the address
+    pub fn logs_subscribe(
+        &self,
+        address: &Pubkey,
+        commitment: Option<String>,
+    ) -> Result<SubscriptionStream<LogsNotification>, Error> {
+        self.subscribe(
+            "logsSubscribe",
+            "logsUnsubscribe",
+            json!([{ "mentions": [address.to_string()] }, { "commitment": commitment }]),
+            false,
+        )
+    }
+
+    /// Notifies once when the transaction reaches the commitment, then ends
+    pub fn signature_subscribe(
+        &self,
+        signature: &str,
+        commitment: Option<String>,
+    ) -> Result<SubscriptionStream<SignatureNotification>, Error> {
+        self.subscribe(
+            "signatureSubscribe",
+            "signatureUnsubscribe",
+            json!([signature, { "commitment": commitment }]),
+            true,
+        )
+    }
+
+    /// Notifies every slot processed by the node
+    pub fn slot_subscribe(&self) -> Result<SubscriptionStream<SlotInfo>, Error> {
+        self.subscribe("slotSubscribe", "slotUnsubscribe", json!([]), false)
+    }
+
+    fn subscribe<T>(
+        &self,
+        method: &'static str,
+        unsubscribe_method: &'static str,
+        params: Value,
+        one_shot: bool,
+    ) -> Result<SubscriptionStream<T>, Error> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = stream_channel();
+
+        self.commands
+            .send(Command::Subscribe(
+                id,
+                Subscription {
+                    method,
+                    unsubscribe_method,
+                    params,
+                    sender,
+                    one_shot,
+                    server_id: None,
+                },
+            ))
+            .map_err(|_| Error::FetchError("The pubsub connection was closed".to_string()))?;
+
+        Ok(SubscriptionStream {
+            id,
+            receiver,
+            commands: self.commands.clone(),
+            _notification: PhantomData,
+        })
+    }
+}
+
+/// Owns the connection. Exits once every handle is dropped or reconnecting gives up
+async fn run(url: String, mut commands: CommandReceiver<Command>, reconnect_policy: RetryPolicy) {
+    let mut subscriptions: HashMap<u64, Subscription> = HashMap::new();
+    // Connects that failed or dropped before a subscription was confirmed, in a row
+    let mut failed_connects = 0;
+    let mut first_connect = true;
+
+    loop {
+        // Waits even after a stable connection dropped, so a server that keeps accepting and
+        // closing doesn't get hammered
+        if !first_connect {
+            let delay = reconnect_policy.delay(failed_connects.max(1) - 1, None);
+            if !wait_for_reconnect(delay, &mut commands, &mut subscriptions).await {
+                return;
+            }
+        }
+        first_connect = false;
+
+        let mut ws = match connect_async(url.as_str()).await {
+            Ok((ws, _)) => ws,
+            Err(_) => {
+                failed_connects += 1;
+                if failed_connects >= reconnect_policy.max_attempts {
+                    return;
+                }
+                continue;
+            }
+        };
+
+        match serve(&mut ws, &mut commands, &mut subscriptions).await {
+            ConnectionEnd::Shutdown => {
+                let _ = ws.close(None).await;
+                return;
+            }
+            ConnectionEnd::Dropped { stable: true } => failed_connects = 0,
+            ConnectionEnd::Dropped { stable: false } => {
+                failed_connects += 1;
+                if failed_connects >= reconnect_policy.max_attempts {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+enum ConnectionEnd {
+    /// Every handle was dropped
+    Shutdown,
+    /// The connection was lost. Stable when a subscription was confirmed on it, or it stayed
+    /// open long enough
+    Dropped { stable: bool },
+}
+
+/// Resubscribes, then relays (un)subscribes and notifications until the connection drops
+async fn serve(
+    ws: &mut WebSocket,
+    commands: &mut CommandReceiver<Command>,
+    subscriptions: &mut HashMap<u64, Subscription>,
+) -> ConnectionEnd {
+    let mut connection = Connection::default();
+    let mut stable = false;
+
+    // Anything subscribed before the connection dropped has to be requested again
+    for (id, subscription) in subscriptions.iter_mut() {
+        subscription.server_id = None;
+        if !connection.request_subscribe(ws, *id, subscription).await {
+            return ConnectionEnd::Dropped { stable };
+        }
+    }
+
+    let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+    let stable_timer = tokio::time::sleep(STABLE_CONNECTION);
+    tokio::pin!(stable_timer);
+
+    loop {
+        tokio::select! {
+            command = commands.recv() => match command {
+                Some(Command::Subscribe(id, subscription)) => {
+                    let sent = connection.request_subscribe(ws, id, &subscription).await;
+                    subscriptions.insert(id, subscription);
+                    if !sent {
+                        break;
+                    }
+                }
+                Some(Command::Unsubscribe(id)) => {
+                    if let Some(subscription) = subscriptions.remove(&id) {
+                        if !connection.request_unsubscribe(ws, id, &subscription).await {
+                            break;
+                        }
+                    }
+                }
+                None => return ConnectionEnd::Shutdown,
+            },
+            message = ws.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    stable |= connection.handle_message(&text, subscriptions);
+                    if !connection.unsubscribe_cancelled(ws).await {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            _ = keepalive.tick() => {
+                if ws.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+            }
+            _ = &mut stable_timer, if !stable => stable = true,
+        }
+    }
+
+    ConnectionEnd::Dropped { stable }
+}
+
+/// Sleeps before the next connect while keeping track of (un)subscribes.
+/// Returns false when every handle has been dropped
+async fn wait_for_reconnect(
+    delay: Duration,
+    commands: &mut CommandReceiver<Command>,
+    subscriptions: &mut HashMap<u64, Subscription>,
+) -> bool {
+    let sleep = tokio::time::sleep(delay);
+    tokio::pin!(sleep);
+
+    loop {
+        tokio::select! {
+            _ = &mut sleep => return true,
+            command = commands.recv() => match command {
+                Some(Command::Subscribe(id, subscription)) => {
+                    subscriptions.insert(id, subscription);
+                }
+                Some(Command::Unsubscribe(id)) => {
+                    subscriptions.remove(&id);
+                }
+                None => return false,
+            },
+        }
+    }
+}
+
+/// Per-connection bookkeeping, request and subscription ids don't survive a reconnect
+#[derive(Default)]
+struct Connection {
+    next_request_id: u64,
+    pending: HashMap<u64, u64>,
+    by_server_id: HashMap<u64, u64>,
+    /// Subscribe requests whose stream was dropped before the server confirmed them,
+    /// with the method that undoes them
+    cancelled: HashMap<u64, &'static str>,
+    /// Server subscriptions of dropped streams, waiting to be unsubscribed
+    orphaned: Vec<(&'static str, u64)>,
+}
+
+impl Connection {
+    async fn request_subscribe(
+        &mut self,
+        ws: &mut WebSocket,
+        id: u64,
+        subscription: &Subscription,
+    ) -> bool {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        self.pending.insert(request_id, id);
+
+        send_json(
+            ws,
+            json!({
+                "jsonrpc": "2.0",
+                "id": request_id,
+                "method": subscription.method,
+                "params": subscription.params,
+            }),
+        )
+        .await
+    }
+
+    async fn request_unsubscribe(
+        &mut self,
+        ws: &mut WebSocket,
+        id: u64,
+        subscription: &Subscription,
+    ) -> bool {
+        let server_id = match subscription.server_id {
+            Some(server_id) => server_id,
+            None => {
+                // Not confirmed yet, unsubscribe once the server hands out its id
+                if let Some(request_id) = self
+                    .pending
+                    .iter()
+                    .find_map(|(request_id, pending)| (*pending == id).then_some(*request_id))
+                {
+                    self.pending.remove(&request_id);
+                    self.cancelled
+                        .insert(request_id, subscription.unsubscribe_method);
+                }
+                return true;
+            }
+        };
+        self.by_server_id.remove(&server_id);
+
+        self.send_unsubscribe(ws, subscription.unsubscribe_method, server_id)
+            .await
+    }
+
+    async fn send_unsubscribe(
+        &mut self,
+        ws: &mut WebSocket,
+        method: &'static str,
+        server_id: u64,
+    ) -> bool {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+
+        send_json(
+            ws,
+            json!({
+                "jsonrpc": "2.0",
+                "id": request_id,
+                "method": method,
+                "params": [server_id],
+            }),
+        )
+        .await
+    }
+
+    /// Unsubscribes the server subscriptions whose stream was dropped before they were confirmed
+    async fn unsubscribe_cancelled(&mut self, ws: &mut WebSocket) -> bool {
+        for (method, server_id) in std::mem::take(&mut self.orphaned) {
+            if !self.send_unsubscribe(ws, method, server_id).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Routes a message to its subscription. Returns true when it confirmed a subscription
+    fn handle_message(
+        &mut self,
+        text: &str,
+        subscriptions: &mut HashMap<u64, Subscription>,
+    ) -> bool {
+        let message = match serde_json::from_str::<Value>(text) {
+            Ok(message) => message,
+            Err(_) => return false,
+        };
+
+        // Response to a (un)subscribe request
+        if let Some(request_id) = message["id"].as_u64() {
+            if let Some(method) = self.cancelled.remove(&request_id) {
+                if let Some(server_id) = message["result"].as_u64() {
+                    self.orphaned.push((method, server_id));
+                }
+                return false;
+            }
+
+            let id = match self.pending.remove(&request_id) {
+                Some(id) => id,
+                None => return false,
+            };
+
+            match message["result"].as_u64() {
+                Some(server_id) => {
+                    if let Some(subscription) = subscriptions.get_mut(&id) {
+                        subscription.server_id = Some(server_id);
+                        self.by_server_id.insert(server_id, id);
+                        return true;
+                    }
+                }
+                None => {
+                    if let Some(subscription) = subscriptions.remove(&id) {
+                        let e = parse_rpc_error(&message).unwrap_or_else(|| {
+                            Error::ParseError("Subscribe returned no subscription id".to_string())
+                        });
+                        let _ = subscription.sender.unbounded_send(Err(e));
+                    }
+                }
+            }
+            return false;
+        }
+
+        // Notification for an active subscription
+        let params = &message["params"];
+        let id = match params["subscription"]
+            .as_u64()
+            .and_then(|server_id| self.by_server_id.get(&server_id))
+        {
+            Some(id) => *id,
+            None => return false,
+        };
+
+        if let Some(subscription) = subscriptions.get(&id) {
+            let _ = subscription
+                .sender
+                .unbounded_send(Ok(params["result"].clone()));
+
+            if subscription.one_shot {
+                subscriptions.remove(&id);
+            }
+        }
+
+        false
+    }
+}
+
+async fn send_json(ws: &mut WebSocket, value: Value) -> bool {
+    ws.send(Message::Text(value.to_string())).await.is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    use super::*;
+
+    type ServerSocket = WebSocketStream<TcpStream>;
+
+    async fn accept(listener: &TcpListener) -> ServerSocket {
+        let (stream, _) = listener.accept().await.unwrap();
+        accept_async(stream).await.unwrap()
+    }
+
+    /// Next JSON-RPC request sent by the client, skipping pings
+    async fn next_request(ws: &mut ServerSocket) -> Value {
+        loop {
+            match ws.next().await.unwrap().unwrap() {
+                Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+                _ => continue,
+            }
+        }
+    }
+
+    async fn confirm(ws: &mut ServerSocket, request: &Value, server_id: u64) {
+        let response = json!({ "jsonrpc": "2.0", "result": server_id, "id": request["id"] });
+        ws.send(Message::Text(response.to_string())).await.unwrap();
+    }
+
+    async fn notify_slot(ws: &mut ServerSocket, server_id: u64, slot: u64) {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "slotNotification",
+            "params": {
+                "result": { "parent": slot - 1, "root": slot - 32, "slot": slot },
+                "subscription": server_id,
+            },
+        });
+        ws.send(Message::Text(notification.to_string()))
+            .await
+            .unwrap();
+    }
+
+    fn client(listener: &TcpListener) -> SolanaMirrorPubsubClient {
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        SolanaMirrorPubsubClient::with_reconnect_policy(
+            url,
+            RetryPolicy {
+                max_attempts: 3,
+                base_delay: Duration::from_millis(10),
+                max_delay: Duration::from_millis(50),
+                jitter: false,
+                respect_retry_after: false,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn resubscribes_after_the_connection_drops() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let pubsub = client(&listener);
+        let mut slots = pubsub.slot_subscribe().unwrap();
+
+        let mut ws = accept(&listener).await;
+        let request = next_request(&mut ws).await;
+        assert_eq!(request["method"], "slotSubscribe");
+        confirm(&mut ws, &request, 7).await;
+        notify_slot(&mut ws, 7, 100).await;
+        assert_eq!(slots.next().await.unwrap().unwrap().slot, 100);
+
+        // The server goes away, the client comes back and subscribes again on its own
+        ws.close(None).await.unwrap();
+        drop(ws);
+
+        let mut ws = accept(&listener).await;
+        let request = next_request(&mut ws).await;
+        assert_eq!(request["method"], "slotSubscribe");
+        confirm(&mut ws, &request, 8).await;
+        notify_slot(&mut ws, 8, 101).await;
+        assert_eq!(slots.next().await.unwrap().unwrap().slot, 101);
+
+        drop(slots);
+        let request = next_request(&mut ws).await;
+        assert_eq!(request["method"], "slotUnsubscribe");
+        assert_eq!(request["params"], json!([8]));
+    }
+
+    #[tokio::test]
+    async fn unsubscribes_streams_dropped_before_confirmation() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let pubsub = client(&listener);
+        let slots = pubsub.slot_subscribe().unwrap();
+
+        let mut ws = accept(&listener).await;
+        let request = next_request(&mut ws).await;
+        assert_eq!(request["method"], "slotSubscribe");
+
+        drop(slots);
+        // Gives the client time to see the drop before the confirmation arrives
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        confirm(&mut ws, &request, 9).await;
+
+        let request = next_request(&mut ws).await;
+        assert_eq!(request["method"], "slotUnsubscribe");
+        assert_eq!(request["params"], json!([9]));
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_servers_that_close_right_away() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let pubsub = client(&listener);
+        let mut slots = pubsub.slot_subscribe().unwrap();
+
+        // Every connection is accepted and closed before the subscription is confirmed
+        for _ in 0..3 {
+            let mut ws = accept(&listener).await;
+            next_request(&mut ws).await;
+            ws.close(None).await.unwrap();
+        }
+
+        let ended = tokio::time::timeout(Duration::from_secs(5), slots.next()).await;
+        assert!(matches!(ended, Ok(None)));
+    }
+}
@@ -0,0 +1,84 @@
+use std::{future::Future, time::Duration};
+
+use rand::Rng;
+
+use crate::enums::Error;
+
+/// Controls how failed requests are retried
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on every following attempt
+    pub base_delay: Duration,
+    /// Upper bound for the computed backoff and for the `Retry-After` the server asks for
+    pub max_delay: Duration,
+    /// Randomizes each delay between half and all of the computed backoff
+    pub jitter: bool,
+    /// Waits for the `Retry-After` the server asked for instead of the computed backoff,
+    /// capped at `max_delay`
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that sends every request exactly once
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Computes how long to wait before the given retry (0 being the first retry)
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if self.respect_retry_after {
+            if let Some(retry_after) = retry_after {
+                return retry_after.min(self.max_delay);
+            }
+        }
+
+        let backoff = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(retry))
+            .min(self.max_delay);
+
+        if !self.jitter || backoff.is_zero() {
+            return backoff;
+        }
+
+        let half = backoff / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+    }
+}
+
+/// Runs `callback` until it succeeds, fails with a non-retryable error or the policy runs out of attempts
+pub async fn retry<T, F, Fut>(policy: &RetryPolicy, callback: F) -> Result<T, Error>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        match callback().await {
+            Ok(res) => return Ok(res),
+            Err(e) if e.is_retryable() && attempt < policy.max_attempts => {
+                tokio::time::sleep(policy.delay(attempt - 1, e.retry_after())).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    sync::Mutex,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use reqwest::{
+    header::{CONTENT_TYPE, RETRY_AFTER},
+    Client, Response,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::enums::Error;
+
+/// Carries serialized JSON-RPC payloads to a node and returns the raw JSON response
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Sends a single JSON-RPC request
+    async fn send(&self, request: &Value) -> Result<Value, Error>;
+
+    /// Sends several JSON-RPC requests in a single round trip
+    async fn send_batch(&self, requests: &[Value]) -> Result<Value, Error>;
+
+    /// Fetches a JSON document over plain HTTP GET, used for off-RPC APIs like Jupiter
+    async fn get_json(&self, url: &str) -> Result<Value, Error>;
+
+    /// Fetches a url that may serve either an image or JSON, e.g. a token metadata uri.
+    /// Image bodies are never downloaded
+    async fn get_document(&self, url: &str) -> Result<Document, Error>;
+
+    /// Identifies the node behind this transport, used to keep separate request budgets
+    fn endpoint(&self) -> String;
+}
+
+/// What a url fetched with [`Transport::get_document`] serves
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Document {
+    Image,
+    Json(Value),
+}
+
+/// Default transport, POSTs every payload to the RPC URL over HTTP
+pub struct HttpTransport {
+    inner_client: Arc<Client>,
+    pub rpc_url: String,
+}
+
+impl HttpTransport {
+    pub fn new(client: Arc<Client>, rpc_url: String) -> Self {
+        Self {
+            inner_client: client,
+            rpc_url,
+        }
+    }
+
+    async fn post<T: serde::Serialize + ?Sized>(&self, body: &T) -> Result<Value, Error> {
+        let serialized = match serde_json::to_string(body) {
+            Ok(serialized) => serialized,
+            Err(e) => return Err(Error::ParseError(e.to_string())),
+        };
+
+        let req = self
+            .inner_client
+            .post(&self.rpc_url)
+            .header("Content-Type", "application/json")
+            .header("solana-client", "js/0.0.0-development")
+            .body(serialized);
+
+        let response = check_status(req.send().await.map_err(transport_error)?).await?;
+
+        response
+            .json::<Value>()
+            .await
+            .map_err(|e| Error::ParseError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, request: &Value) -> Result<Value, Error> {
+        self.post(request).await
+    }
+
+    async fn send_batch(&self, requests: &[Value]) -> Result<Value, Error> {
+        self.post(requests).await
+    }
+
+    async fn get_json(&self, url: &str) -> Result<Value, Error> {
+        let response = self
+            .inner_client
+            .get(url)
+            .send()
+            .await
+            .map_err(transport_error)?;
+        let response = check_status(response).await?;
+
+        response
+            .json::<Value>()
+            .await
+            .map_err(|e| Error::ParseError(e.to_string()))
+    }
+
+    async fn get_document(&self, url: &str) -> Result<Document, Error> {
+        let response = self
+            .inner_client
+            .get(url)
+            .send()
+            .await
+            .map_err(transport_error)?;
+        let response = check_status(response).await?;
+
+        let is_image = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| content_type.starts_with("image/"));
+        if is_image {
+            // The body is dropped with the response
+            return Ok(Document::Image);
+        }
+
+        response
+            .json::<Value>()
+            .await
+            .map(Document::Json)
+            .map_err(|e| Error::ParseError(e.to_string()))
+    }
+
+    fn endpoint(&self) -> String {
+        self.rpc_url.clone()
+    }
+}
+
+/// Turns non-2xx responses into [`Error::HttpError`], keeping the `Retry-After` hint
+/// and the body
+async fn check_status(response: Response) -> Result<Response, Error> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+    let body = response.text().await.ok();
+
+    Err(Error::HttpError {
+        status: status.as_u16(),
+        retry_after,
+        body,
+    })
+}
+
+/// Connection failures and timeouts are transient, anything else is reported as is
+fn transport_error(e: reqwest::Error) -> Error {
+    if e.is_timeout() || e.is_connect() {
+        Error::TransportError(e.to_string())
+    } else {
+        Error::FetchError(e.to_string())
+    }
+}
+
+/// In-memory transport that serves canned results keyed by method and params.
+/// Meant for exercising the SDK without a live RPC
+#[derive(Default)]
+pub struct MemoryTransport {
+    responses: Mutex<HashMap<String, Value>>,
+    documents: Mutex<HashMap<String, Value>>,
+    images: Mutex<HashSet<String>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the `result` returned for every request matching `method` and `params`
+    pub fn insert(&self, method: &str, params: Value, result: Value) {
+        self.responses
+            .lock()
+            .unwrap()
+            .insert(request_key(method, &params), result);
+    }
+
+    /// Builder flavour of [`MemoryTransport::insert`]
+    pub fn with_response(self, method: &str, params: Value, result: Value) -> Self {
+        self.insert(method, params, result);
+        self
+    }
+
+    /// Registers the document returned for HTTP GETs to `url`
+    pub fn insert_json(&self, url: &str, body: Value) {
+        self.documents.lock().unwrap().insert(url.to_string(), body);
+    }
+
+    /// Makes `url` serve an image
+    pub fn insert_image(&self, url: &str) {
+        self.images.lock().unwrap().insert(url.to_string());
+    }
+
+    fn respond(&self, request: &Value) -> Result<Value, Error> {
+        let method = request["method"].as_str().unwrap_or_default();
+        let key = request_key(method, &request["params"]);
+
+        match self.responses.lock().unwrap().get(&key) {
+            Some(result) => Ok(json!({
+                "jsonrpc": "2.0",
+                "result": result,
+                "id": request["id"],
+            })),
+            None => Err(Error::FetchError(format!("No canned response for {}", key))),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn send(&self, request: &Value) -> Result<Value, Error> {
+        self.respond(request)
+    }
+
+    async fn send_batch(&self, requests: &[Value]) -> Result<Value, Error> {
+        let responses = requests
+            .iter()
+            .map(|request| self.respond(request))
+            .collect::<Result<Vec<Value>, Error>>()?;
+
+        Ok(Value::Array(responses))
+    }
+
+    async fn get_json(&self, url: &str) -> Result<Value, Error> {
+        match self.documents.lock().unwrap().get(url) {
+            Some(body) => Ok(body.clone()),
+            None => Err(Error::FetchError(format!("No canned document for {}", url))),
+        }
+    }
+
+    async fn get_document(&self, url: &str) -> Result<Document, Error> {
+        if self.images.lock().unwrap().contains(url) {
+            return Ok(Document::Image);
+        }
+
+        self.get_json(url).await.map(Document::Json)
+    }
+
+    fn endpoint(&self) -> String {
+        "memory".to_string()
+    }
+}
+
+/// Identifies a request by its method and params, ignoring the id
+pub(crate) fn request_key(method: &str, params: &Value) -> String {
+    format!("{} {}", method, params)
+}
@@ -1,3 +1,4 @@
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -55,6 +56,15 @@ pub struct AccountInfo {
     pub state: String,
     #[serde(rename = "tokenAmount")]
     pub token_amount: TokenAmount,
+    /// Address allowed to transfer up to `delegated_amount` from the account
+    pub delegate: Option<String>,
+    #[serde(rename = "delegatedAmount")]
+    pub delegated_amount: Option<TokenAmount>,
+    /// Address allowed to close the account, the owner when unset
+    #[serde(rename = "closeAuthority")]
+    pub close_authority: Option<String>,
+    /// Token-2022 account extensions, as returned by jsonParsed
+    pub extensions: Option<Vec<Value>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -93,6 +103,88 @@ pub struct AccountMetadataInfo {
     pub space: u64,
 }
 
+// get_multiple_accounts
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MultipleAccountsResultData {
+    pub context: Context,
+    pub value: Vec<Option<UiAccount>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UiAccount {
+    pub data: UiAccountData,
+    pub executable: bool,
+    pub lamports: u64,
+    pub owner: String,
+    #[serde(rename = "rentEpoch")]
+    pub rent_epoch: u64,
+    pub space: Option<u64>,
+}
+
+impl UiAccount {
+    /// Decodes base64 account data. Returns None for jsonParsed data
+    pub fn decode_data(&self) -> Option<Vec<u8>> {
+        match &self.data {
+            UiAccountData::Binary(data, encoding) if encoding == "base64" => {
+                base64::prelude::BASE64_STANDARD.decode(data).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `[data, encoding]` for binary encodings, or the parsed account when using jsonParsed
+/// on an account owned by a program the node knows how to parse
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum UiAccountData {
+    Binary(String, String),
+    Json(ParsedAccountData),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ParsedAccountData {
+    pub program: String,
+    pub parsed: Value,
+    pub space: u64,
+}
+
+// get_program_accounts
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KeyedAccount {
+    pub pubkey: String,
+    pub account: UiAccount,
+}
+
+/// Plain list of accounts, or the list and the slot it was read at when `withContext` is set
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ProgramAccountsResultData {
+    WithContext {
+        context: Context,
+        value: Vec<KeyedAccount>,
+    },
+    Accounts(Vec<KeyedAccount>),
+}
+
+impl ProgramAccountsResultData {
+    pub fn context(&self) -> Option<&Context> {
+        match self {
+            ProgramAccountsResultData::WithContext { context, .. } => Some(context),
+            ProgramAccountsResultData::Accounts(_) => None,
+        }
+    }
+
+    pub fn into_accounts(self) -> Vec<KeyedAccount> {
+        match self {
+            ProgramAccountsResultData::WithContext { value, .. } => value,
+            ProgramAccountsResultData::Accounts(accounts) => accounts,
+        }
+    }
+}
+
 // get_decimals
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -111,6 +203,57 @@ pub struct Decimals {
     pub ui_amount_string: String,
 }
 
+// get_token_largest_accounts
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TokenLargestAccountsResultData {
+    pub context: Context,
+    pub value: Vec<TokenLargestAccount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TokenLargestAccount {
+    pub address: String,
+    pub amount: String,
+    pub decimals: u8,
+    #[serde(rename = "uiAmount")]
+    pub ui_amount: Option<f64>,
+    #[serde(rename = "uiAmountString")]
+    pub ui_amount_string: String,
+}
+
+// get_epoch_info
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EpochInfo {
+    #[serde(rename = "absoluteSlot")]
+    pub absolute_slot: u64,
+    #[serde(rename = "blockHeight")]
+    pub block_height: u64,
+    pub epoch: u64,
+    #[serde(rename = "slotIndex")]
+    pub slot_index: u64,
+    #[serde(rename = "slotsInEpoch")]
+    pub slots_in_epoch: u64,
+    #[serde(rename = "transactionCount")]
+    pub transaction_count: Option<u64>,
+}
+
+// get_latest_blockhash
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LatestBlockhashResultData {
+    pub context: Context,
+    pub value: LatestBlockhash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LatestBlockhash {
+    pub blockhash: String,
+    #[serde(rename = "lastValidBlockHeight")]
+    pub last_valid_block_height: u64,
+}
+
 // get_signatures_for_address
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -237,3 +380,53 @@ pub struct Header {
     #[serde(rename = "numRequiredSignatures")]
     pub num_required_signatures: u8,
 }
+
+// pubsub
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotificationContext {
+    pub slot: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Notification<T> {
+    pub context: NotificationContext,
+    pub value: T,
+}
+
+pub type AccountNotification = Notification<NotifiedAccount>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotifiedAccount {
+    /// jsonParsed data when the owner program is known, `[data, encoding]` otherwise
+    pub data: Value,
+    pub executable: bool,
+    pub lamports: u64,
+    pub owner: String,
+    #[serde(rename = "rentEpoch")]
+    pub rent_epoch: u64,
+    pub space: Option<u64>,
+}
+
+pub type LogsNotification = Notification<Logs>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Logs {
+    pub signature: String,
+    pub err: Option<Value>,
+    pub logs: Vec<String>,
+}
+
+pub type SignatureNotification = Notification<SignatureStatus>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignatureStatus {
+    pub err: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SlotInfo {
+    pub parent: u64,
+    pub root: u64,
+    pub slot: u64,
+}
@@ -1,6 +1,8 @@
-use std::fmt;
+use std::{fmt, time::Duration};
 
-#[derive(Debug)]
+use serde_json::Value;
+
+#[derive(Debug, Clone)]
 pub enum Error {
     InvalidAddress,
     InvalidIndex,
@@ -8,17 +10,162 @@ pub enum Error {
     FetchError(String),
     ParseError(String),
     TooManyRequests,
+    UnmatchedRequest(String),
+    HttpError {
+        status: u16,
+        retry_after: Option<Duration>,
+        /// Body of the response, APIs like Jupiter explain the error there
+        body: Option<String>,
+    },
+    TransportError(String),
+    RpcError(RpcError),
+    MissingResponse(String),
+}
+
+impl Error {
+    /// Whether the request that produced this error is worth sending again
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::TooManyRequests | Error::TransportError(_) | Error::MissingResponse(_) => true,
+            Error::HttpError { status, .. } => *status == 429 || *status >= 500,
+            Error::RpcError(e) => e.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Whether the node couldn't be reached or refused the request as a whole, as opposed
+    /// to answering it with an error
+    pub fn is_transport_error(&self) -> bool {
+        matches!(
+            self,
+            Error::FetchError(_)
+                | Error::TransportError(_)
+                | Error::HttpError { .. }
+                | Error::TooManyRequests
+        )
+    }
+
+    /// How long the server asked us to wait before retrying, if it said so
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::HttpError { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Error::InvalidAddress => todo!(),
-            Error::InvalidIndex => todo!(),
-            Error::InvalidTimeframe => todo!(),
+            Error::InvalidAddress => write!(f, "Invalid address"),
+            Error::InvalidIndex => write!(f, "Invalid index"),
+            Error::InvalidTimeframe => write!(f, "Invalid timeframe"),
             Error::FetchError(msg) => write!(f, "Fetch error: {}", msg),
             Error::ParseError(msg) => write!(f, "Parse error: {}", msg),
-            Error::TooManyRequests => todo!(),
+            Error::TooManyRequests => write!(f, "Too many requests"),
+            Error::UnmatchedRequest(key) => write!(f, "No recorded response for {}", key),
+            Error::HttpError { status, .. } => write!(f, "HTTP error: status {}", status),
+            Error::TransportError(msg) => write!(f, "Transport error: {}", msg),
+            Error::RpcError(e) => write!(f, "RPC error: {}", e),
+            Error::MissingResponse(id) => write!(f, "No response for batch request {}", id),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Error object returned by the node, with the well-known Solana codes decoded into [`RpcErrorKind`]
+#[derive(Debug, Clone)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    /// Raw `data` field, its shape depends on the error
+    pub data: Option<Value>,
+    pub kind: RpcErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpcErrorKind {
+    /// -32001, the block was purged from the node's ledger
+    BlockCleanedUp,
+    /// -32004
+    BlockNotAvailable,
+    /// -32005, the node is unhealthy and lagging behind the cluster
+    NodeBehind {
+        num_slots_behind: Option<u64>,
+    },
+    /// -32007 and -32009, the slot was skipped or is missing in long-term storage
+    SlotSkipped,
+    /// -32011
+    TransactionHistoryNotAvailable,
+    /// -32014
+    BlockStatusNotAvailableYet,
+    /// -32015, the request needs a higher `maxSupportedTransactionVersion`
+    UnsupportedTransactionVersion,
+    /// -32016, the node hasn't reached the requested `minContextSlot` yet
+    MinContextSlotNotReached {
+        context_slot: Option<u64>,
+    },
+    /// -32019, the node can't reach its long-term storage (BigTable)
+    LongTermStorageUnavailable,
+    /// -32600
+    InvalidRequest,
+    /// -32601
+    MethodNotFound,
+    /// -32602
+    InvalidParams,
+    /// -32603
+    InternalError,
+    Other,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: String, data: Option<Value>) -> Self {
+        let kind = match code {
+            -32001 => RpcErrorKind::BlockCleanedUp,
+            -32004 => RpcErrorKind::BlockNotAvailable,
+            -32005 => RpcErrorKind::NodeBehind {
+                num_slots_behind: data
+                    .as_ref()
+                    .and_then(|data| data["numSlotsBehind"].as_u64()),
+            },
+            -32007 | -32009 => RpcErrorKind::SlotSkipped,
+            -32011 => RpcErrorKind::TransactionHistoryNotAvailable,
+            -32014 => RpcErrorKind::BlockStatusNotAvailableYet,
+            -32015 => RpcErrorKind::UnsupportedTransactionVersion,
+            -32016 => RpcErrorKind::MinContextSlotNotReached {
+                context_slot: data.as_ref().and_then(|data| data["contextSlot"].as_u64()),
+            },
+            -32019 => RpcErrorKind::LongTermStorageUnavailable,
+            -32600 => RpcErrorKind::InvalidRequest,
+            -32601 => RpcErrorKind::MethodNotFound,
+            -32602 => RpcErrorKind::InvalidParams,
+            -32603 => RpcErrorKind::InternalError,
+            _ => RpcErrorKind::Other,
+        };
+
+        Self {
+            code,
+            message,
+            data,
+            kind,
         }
     }
+
+    /// Errors caused by the node's state rather than the request, another attempt may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind,
+            RpcErrorKind::NodeBehind { .. }
+                | RpcErrorKind::MinContextSlotNotReached { .. }
+                | RpcErrorKind::BlockStatusNotAvailableYet
+                | RpcErrorKind::LongTermStorageUnavailable
+        )
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
 }
@@ -3,6 +3,7 @@ mod chart;
 mod client;
 mod consts;
 mod enums;
+mod metadata;
 mod price;
 mod solana_mirror;
 mod transactions;
@@ -11,7 +12,44 @@ mod utils;
 
 pub use solana_mirror::SolanaMirror;
 
-pub use balances::{accounts::ParsedAta, dapps::types::ParsedPosition};
-pub use chart::types::{Timeframe, ChartData};
+pub use balances::{
+    accounts::{types::ParsedMetadata, ParsedAta},
+    audit::{DelegateApproval, FrozenAccount, TokenAudit},
+    dapps::types::ParsedPosition,
+    mints::{
+        ExtensionRisk, InterestBearing, MetadataPointer, MintInfo, TokenExtensions, TokenMetadata,
+        TransferFee,
+    },
+    nfts::{EditionInfo, NftCollection, NftCollectionKey, NftCreator, NftStandard, ParsedNft},
+    reclaim::{ClosableAccount, CloseTransaction, RentReclaimReport, MAX_CLOSE_PER_TRANSACTION},
+    risk::{HolderShare, MintRiskProfile},
+    sol::SolBreakdown,
+    spam::{SpamClassifier, SpamPolicy, SpamSignal, SpamVerdict},
+    stake::{ParsedStakeAccount, StakeActivation, StakeLockup},
+    types::BalancesResponse,
+};
+pub use chart::types::{ChartData, Timeframe};
+pub use client::{
+    cassette::{Interaction, RecordingTransport, ReplayTransport},
+    limiter::RateLimit,
+    pool::{EndpointPool, EndpointStats},
+    pubsub::{SolanaMirrorPubsubClient, SubscriptionStream},
+    retry::RetryPolicy,
+    transport::{Document, HttpTransport, MemoryTransport, Transport},
+    types::{
+        AccountNotification, KeyedAccount, Logs, LogsNotification, Notification,
+        NotificationContext, NotifiedAccount, ParsedAccountData, ProgramAccountsResultData,
+        SignatureNotification, SignatureStatus, SlotInfo, TokenLargestAccount, UiAccount,
+        UiAccountData,
+    },
+    DataSlice, GetMultipleAccountsConfig, GetProgramAccountsConfig, Memcmp, RpcFilter,
+    SolanaMirrorRpcClient, MAX_MULTIPLE_ACCOUNTS,
+};
+pub use enums::{Error, RpcError, RpcErrorKind};
+pub use metadata::{
+    AssetDetail, Attribute, Cache, FileCache, ImageResolver, MemoryCache, MetadataResolver,
+    MetadataSource, OffChainCollection, OffChainCreator, OffChainExtensions, OffChainFile,
+    OffChainMetadata, OffChainProperties, TokenListEntry, TokenRegistry,
+};
+pub use price::{LstOracle, LstSource};
 pub use transactions::types::TransactionResponse;
-pub use enums::Error;
@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+use crate::enums::Error;
+
+/// Storage for resolved metadata and images. Values are plain JSON so any backend
+/// (memory, disk, Redis...) can hold them
+#[async_trait]
+pub trait Cache: Send + Sync {
+    async fn get(&self, key: &str) -> Option<Value>;
+    async fn set(&self, key: &str, value: Value);
+}
+
+struct MemoryEntry {
+    value: Value,
+    inserted_at: Instant,
+}
+
+/// In-process cache whose entries expire after `ttl`. Once `max_entries` is reached
+/// the oldest entry is evicted
+pub struct MemoryCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, MemoryEntry>>,
+}
+
+impl MemoryCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries: max_entries.max(1),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(60 * 60), 10_000)
+    }
+}
+
+#[async_trait]
+impl Cache for MemoryCache {
+    async fn get(&self, key: &str) -> Option<Value> {
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get(key) {
+            Some(entry) if entry.inserted_at.elapsed() < self.ttl => Some(entry.value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn set(&self, key: &str, value: Value) {
+        let mut entries = self.entries.lock().unwrap();
+
+        if !entries.contains_key(key) && entries.len() >= self.max_entries {
+            entries.retain(|_, entry| entry.inserted_at.elapsed() < self.ttl);
+        }
+
+        if !entries.contains_key(key) && entries.len() >= self.max_entries {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.inserted_at)
+                .map(|(key, _)| key.clone());
+
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+
+        entries.insert(
+            key.to_string(),
+            MemoryEntry {
+                value,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileEntry {
+    /// Unix timestamp in seconds
+    #[serde(rename = "expiresAt")]
+    expires_at: u64,
+    value: Value,
+}
+
+/// Cache stored as one JSON file per key in a directory, so it survives restarts
+/// and can be shared by several processes
+pub struct FileCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl FileCache {
+    /// Uses `dir` for the cache files, creating it if needed
+    pub fn new<P: Into<PathBuf>>(dir: P, ttl: Duration) -> Result<Self, Error> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| Error::FetchError(e.to_string()))?;
+
+        Ok(Self { dir, ttl })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let file_name: String = key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        self.dir.join(format!("{}.json", file_name))
+    }
+}
+
+#[async_trait]
+impl Cache for FileCache {
+    async fn get(&self, key: &str) -> Option<Value> {
+        let contents = tokio::fs::read(self.path(key)).await.ok()?;
+        let entry = serde_json::from_slice::<FileEntry>(&contents).ok()?;
+
+        if entry.expires_at <= unix_timestamp() {
+            return None;
+        }
+
+        Some(entry.value)
+    }
+
+    async fn set(&self, key: &str, value: Value) {
+        let entry = FileEntry {
+            expires_at: unix_timestamp() + self.ttl.as_secs(),
+            value,
+        };
+        let contents = match serde_json::to_vec(&entry) {
+            Ok(contents) => contents,
+            Err(_) => return,
+        };
+
+        // Write then rename so other processes never read a half written file
+        let path = self.path(key);
+        let tmp_path = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+
+        if tokio::fs::write(&tmp_path, contents).await.is_ok()
+            && tokio::fs::rename(&tmp_path, &path).await.is_err()
+        {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+        }
+    }
+}
+
+/// One lock per cache key, so concurrent lookups of the same key wait for the first
+/// one to fill the cache while lookups of other keys go ahead
+#[derive(Default)]
+pub struct KeyLocks {
+    locks: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+}
+
+impl KeyLocks {
+    /// Locks every key, in a fixed order so two callers can't deadlock each other
+    pub async fn lock_all(&self, keys: &[String]) -> Vec<OwnedMutexGuard<()>> {
+        let mut keys: Vec<&String> = keys.iter().collect();
+        keys.sort();
+        keys.dedup();
+
+        let mut guards = Vec::with_capacity(keys.len());
+        for key in keys {
+            guards.push(self.lock(key).await);
+        }
+
+        guards
+    }
+
+    pub async fn lock(&self, key: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+
+            // Drop the locks nobody holds or waits for anymore
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+
+            locks
+                .entry(key.to_string())
+                .or_insert_with(|| Arc::new(AsyncMutex::new(())))
+                .clone()
+        };
+
+        lock.lock_owned().await
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
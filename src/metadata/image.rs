@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+    client::{transport::Document, SolanaMirrorRpcClient},
+    consts::{SOL_ADDRESS, SOL_IMAGE, USDC_ADDRESS, USDC_IMAGE},
+};
+
+const DEFAULT_IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+const DEFAULT_ARWEAVE_GATEWAY: &str = "https://arweave.net/";
+
+/// Finds the image of a token from its metadata uri. Decentralized storage uris are
+/// rewritten through HTTP gateways, and only urls that serve an image are kept
+#[derive(Clone)]
+pub struct ImageResolver {
+    ipfs_gateway: String,
+    arweave_gateway: String,
+    /// Images used as is for these mints, without looking at their metadata
+    images: HashMap<String, String>,
+}
+
+impl Default for ImageResolver {
+    fn default() -> Self {
+        Self {
+            ipfs_gateway: DEFAULT_IPFS_GATEWAY.to_string(),
+            arweave_gateway: DEFAULT_ARWEAVE_GATEWAY.to_string(),
+            images: HashMap::from([
+                (USDC_ADDRESS.to_string(), USDC_IMAGE.to_string()),
+                (SOL_ADDRESS.to_string(), SOL_IMAGE.to_string()),
+            ]),
+        }
+    }
+}
+
+impl ImageResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gateway `ipfs://<cid>` uris are fetched through, e.g. `https://cloudflare-ipfs.com/ipfs/`
+    pub fn with_ipfs_gateway(mut self, gateway: &str) -> Self {
+        self.ipfs_gateway = with_trailing_slash(gateway);
+        self
+    }
+
+    /// Gateway `ar://<id>` uris are fetched through, e.g. `https://arweave.net/`
+    pub fn with_arweave_gateway(mut self, gateway: &str) -> Self {
+        self.arweave_gateway = with_trailing_slash(gateway);
+        self
+    }
+
+    /// Always uses `image` for the mint
+    pub fn with_image(mut self, mint: &str, image: &str) -> Self {
+        self.images.insert(mint.to_string(), image.to_string());
+        self
+    }
+
+    /// Replaces the whole table of predefined images, keyed by mint
+    pub fn with_images(mut self, images: HashMap<String, String>) -> Self {
+        self.images = images;
+        self
+    }
+
+    /// Returns the predefined image of the mint, if any
+    pub fn predefined_image(&self, mint: &str) -> Option<String> {
+        self.images.get(mint).cloned()
+    }
+
+    /// Turns `ipfs://` and `ar://` uris into gateway urls, other uris are returned as is
+    pub fn rewrite_uri(&self, uri: &str) -> String {
+        let uri = uri.trim();
+
+        if let Some(path) = uri.strip_prefix("ipfs://") {
+            let path = path.strip_prefix("ipfs/").unwrap_or(path);
+            format!("{}{}", self.ipfs_gateway, path)
+        } else if let Some(path) = uri.strip_prefix("ar://") {
+            format!("{}{}", self.arweave_gateway, path)
+        } else {
+            uri.to_string()
+        }
+    }
+
+    /// Resolves the image from the metadata uri. The uri may point to the image itself or
+    /// to off-chain JSON metadata, where `image` is tried first and then the image entries
+    /// of `properties.files`. Returns an empty string when no candidate serves an image
+    pub async fn resolve(&self, client: &SolanaMirrorRpcClient, uri: &str) -> String {
+        self.resolve_document(client, uri).await.0
+    }
+
+    /// Like [`ImageResolver::resolve`], also handing back what the metadata uri served so
+    /// it isn't fetched again. None when it couldn't be fetched
+    pub(crate) async fn resolve_document(
+        &self,
+        client: &SolanaMirrorRpcClient,
+        uri: &str,
+    ) -> (String, Option<Document>) {
+        if uri.trim().is_empty() {
+            return (String::new(), None);
+        }
+
+        let uri = self.rewrite_uri(uri);
+        if uri.starts_with("data:image/") {
+            return (uri, Some(Document::Image));
+        }
+
+        let document = match self.fetch(client, &uri).await {
+            Some(document) => document,
+            None => return (String::new(), None),
+        };
+        let json = match &document {
+            Document::Image => return (uri, Some(document)),
+            Document::Json(json) => json,
+        };
+
+        for candidate in image_candidates(json) {
+            let candidate = self.rewrite_uri(&candidate);
+            if candidate != uri && self.is_image(client, &candidate).await {
+                return (candidate, Some(document));
+            }
+        }
+
+        (String::new(), Some(document))
+    }
+
+    /// Whether the url serves an image, judged by its content type
+    async fn is_image(&self, client: &SolanaMirrorRpcClient, url: &str) -> bool {
+        if url.starts_with("data:image/") {
+            return true;
+        }
+
+        matches!(self.fetch(client, url).await, Some(Document::Image))
+    }
+
+    async fn fetch(&self, client: &SolanaMirrorRpcClient, url: &str) -> Option<Document> {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return None;
+        }
+
+        client.get_document(url).await.ok()
+    }
+}
+
+/// Image uris of off-chain metadata, in the order they should be tried
+fn image_candidates(json: &Value) -> Vec<String> {
+    let mut candidates: Vec<String> = Vec::new();
+
+    if let Some(image) = json["image"].as_str() {
+        candidates.push(image.to_string());
+    }
+
+    if let Some(files) = json["properties"]["files"].as_array() {
+        for file in files {
+            let uri = match file {
+                Value::String(uri) => Some(uri.as_str()),
+                Value::Object(_) => {
+                    let file_type = file["type"].as_str().unwrap_or("image/");
+                    file["uri"]
+                        .as_str()
+                        .filter(|_| file_type.starts_with("image/"))
+                }
+                _ => None,
+            };
+
+            if let Some(uri) = uri {
+                if !candidates.iter().any(|candidate| candidate == uri) {
+                    candidates.push(uri.to_string());
+                }
+            }
+        }
+    }
+
+    candidates
+}
+
+fn with_trailing_slash(url: &str) -> String {
+    if url.ends_with('/') {
+        url.to_string()
+    } else {
+        format!("{}/", url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::client::transport::MemoryTransport;
+
+    const URI: &str = "https://example.com/token.json";
+    const IMAGE: &str = "https://example.com/token.png";
+
+    #[tokio::test]
+    async fn resolves_images_through_the_transport() {
+        let transport = MemoryTransport::new();
+        transport.insert_json(
+            URI,
+            json!({ "name": "Token", "image": "https://example.com/missing.png", "properties": { "files": [{ "uri": IMAGE, "type": "image/png" }] } }),
+        );
+        transport.insert_image(IMAGE);
+        let client = SolanaMirrorRpcClient::with_transport(Arc::new(transport));
+
+        let (image, document) = ImageResolver::new().resolve_document(&client, URI).await;
+        assert_eq!(image, IMAGE);
+        assert!(matches!(document, Some(Document::Json(json)) if json["name"] == "Token"));
+
+        assert_eq!(ImageResolver::new().resolve(&client, IMAGE).await, IMAGE);
+    }
+}
@@ -0,0 +1,82 @@
+use std::str::FromStr;
+
+use mpl_token_metadata::{
+    accounts::{Edition, MasterEdition, Metadata},
+    programs::MPL_TOKEN_METADATA_ID,
+    types::Key,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{balances::accounts::types::ParsedMetadata, utils::clean_string};
+
+use super::types::MetadataSource;
+
+/// Gets the metadata account address associated with the mint
+pub fn get_metadata_address(mint_address: &str) -> Option<Pubkey> {
+    let mint_pubkey = Pubkey::from_str(mint_address).ok()?;
+    let mpl_program_id = Pubkey::from_str(MPL_TOKEN_METADATA_ID.to_string().as_str()).unwrap();
+
+    let (metadata_pubkey, _) = Pubkey::find_program_address(
+        &[
+            "metadata".as_ref(),
+            &mpl_program_id.to_bytes(),
+            &mint_pubkey.to_bytes(),
+        ],
+        &mpl_program_id,
+    );
+
+    Some(metadata_pubkey)
+}
+
+/// Gets the edition account address of the mint, which holds either a master edition
+/// or a print edition
+pub fn get_edition_address(mint_address: &str) -> Option<Pubkey> {
+    let mint_pubkey = Pubkey::from_str(mint_address).ok()?;
+    let mpl_program_id = Pubkey::from_str(MPL_TOKEN_METADATA_ID.to_string().as_str()).unwrap();
+
+    let (edition_pubkey, _) = Pubkey::find_program_address(
+        &[
+            "metadata".as_ref(),
+            &mpl_program_id.to_bytes(),
+            &mint_pubkey.to_bytes(),
+            "edition".as_ref(),
+        ],
+        &mpl_program_id,
+    );
+
+    Some(edition_pubkey)
+}
+
+/// Decoded edition account
+pub enum EditionAccount {
+    Master(MasterEdition),
+    Print(Edition),
+}
+
+/// Parses the given edition account data, master editions v1 and v2 share their first fields
+pub fn parse_edition(data: &[u8]) -> Option<EditionAccount> {
+    match data.first()? {
+        key if *key == Key::EditionV1 as u8 => {
+            Edition::from_bytes(data).ok().map(EditionAccount::Print)
+        }
+        key if *key == Key::MasterEditionV1 as u8 || *key == Key::MasterEditionV2 as u8 => {
+            MasterEdition::from_bytes(data)
+                .ok()
+                .map(EditionAccount::Master)
+        }
+        _ => None,
+    }
+}
+
+/// Parses the given metadata account data.
+pub fn parse_metadata(data: &[u8]) -> Option<ParsedMetadata> {
+    let metadata = Metadata::safe_deserialize(data).ok()?;
+
+    Some(ParsedMetadata {
+        name: clean_string(metadata.name),
+        symbol: clean_string(metadata.symbol),
+        uri: clean_string(metadata.uri),
+        source: MetadataSource::Metaplex,
+        mutable: Some(metadata.is_mutable),
+    })
+}
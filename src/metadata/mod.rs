@@ -0,0 +1,593 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    balances::{
+        accounts::types::ParsedMetadata,
+        mints::{fetch_mints, parse_mint_account, MintInfo},
+    },
+    client::{
+        transport::Document, types::UiAccount, GetMultipleAccountsConfig, SolanaMirrorRpcClient,
+    },
+    enums::Error,
+};
+
+use cache::KeyLocks;
+pub use cache::{Cache, FileCache, MemoryCache};
+pub use image::ImageResolver;
+use metaplex::{get_metadata_address, parse_metadata};
+use mpl_core::{parse_core_account, MPL_CORE_PROGRAM_ID};
+pub use registry::{TokenListEntry, TokenRegistry};
+pub use types::{
+    AssetDetail, Attribute, MetadataSource, OffChainCollection, OffChainCreator,
+    OffChainExtensions, OffChainFile, OffChainMetadata, OffChainProperties,
+};
+
+pub mod cache;
+pub mod image;
+pub mod metaplex;
+pub mod mpl_core;
+pub mod registry;
+pub mod types;
+
+/// Resolves the name, symbol and uri of tokens by trying, in order, the local
+/// override list, the token list, the Token-2022 metadata extension, Metaplex token
+/// metadata and MPL Core. Each source is loaded in a single batch for every token it still has to find
+#[derive(Clone)]
+pub struct MetadataResolver {
+    overrides: HashMap<String, ParsedMetadata>,
+    images: ImageResolver,
+    registry: Arc<TokenRegistry>,
+    cache: Arc<dyn Cache>,
+    locks: Arc<KeyLocks>,
+}
+
+impl Default for MetadataResolver {
+    fn default() -> Self {
+        Self {
+            overrides: HashMap::new(),
+            images: ImageResolver::default(),
+            registry: Arc::new(TokenRegistry::default()),
+            cache: Arc::new(MemoryCache::default()),
+            locks: Arc::new(KeyLocks::default()),
+        }
+    }
+}
+
+impl MetadataResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the cache holding resolved metadata and images
+    pub fn with_cache(mut self, cache: Arc<dyn Cache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Replaces the resolver used to find token images, e.g. to change IPFS gateways
+    pub fn with_image_resolver(mut self, images: ImageResolver) -> Self {
+        self.images = images;
+        self
+    }
+
+    /// Uses a token list for verification flags, tags, and the name, symbol and logo of
+    /// the tokens it lists
+    pub fn with_token_registry(mut self, registry: TokenRegistry) -> Self {
+        self.registry = Arc::new(registry);
+        self
+    }
+
+    /// Returns the token list entry of the mint, if it is listed
+    pub fn token_list_entry(&self, mint: &str) -> Option<&TokenListEntry> {
+        self.registry.get(mint)
+    }
+
+    /// Whether a token list was loaded, without one no token counts as verified
+    pub fn has_token_registry(&self) -> bool {
+        !self.registry.is_empty()
+    }
+
+    /// Uses the given metadata for the mint instead of the one found on-chain
+    pub fn with_override(mut self, mint: &str, metadata: ParsedMetadata) -> Self {
+        self.overrides.insert(
+            mint.to_string(),
+            ParsedMetadata {
+                source: MetadataSource::Override,
+                ..metadata
+            },
+        );
+        self
+    }
+
+    /// Loads overrides from a JSON file mapping mints to `{ "name", "symbol", "uri" }`
+    pub fn with_overrides_file<P: AsRef<Path>>(mut self, path: P) -> Result<Self, Error> {
+        let contents = fs::read_to_string(path).map_err(|e| Error::FetchError(e.to_string()))?;
+        let overrides: HashMap<String, ParsedMetadata> =
+            serde_json::from_str(&contents).map_err(|e| Error::ParseError(e.to_string()))?;
+
+        for (mint, metadata) in overrides {
+            self = self.with_override(&mint, metadata);
+        }
+
+        Ok(self)
+    }
+
+    /// Resolves the metadata of every mint. `mint_infos` may hold mints that were
+    /// already fetched, the missing ones are fetched here
+    pub async fn resolve(
+        &self,
+        client: &SolanaMirrorRpcClient,
+        mints: &[String],
+        mint_infos: &HashMap<String, MintInfo>,
+    ) -> HashMap<String, ParsedMetadata> {
+        let mut metadata: HashMap<String, ParsedMetadata> = HashMap::new();
+        let mut missing: Vec<String> = Vec::new();
+
+        for mint in mints {
+            if metadata.contains_key(mint) || missing.contains(mint) {
+                continue;
+            }
+
+            if let Some(overridden) = self.overrides.get(mint) {
+                metadata.insert(mint.to_string(), overridden.clone());
+            } else if let Some(listed) = self.registry.get(mint) {
+                metadata.insert(
+                    mint.to_string(),
+                    ParsedMetadata {
+                        name: listed.name.clone(),
+                        symbol: listed.symbol.clone(),
+                        uri: String::new(),
+                        source: MetadataSource::TokenList,
+                        mutable: None,
+                    },
+                );
+            } else {
+                missing.push(mint.to_string());
+            }
+        }
+
+        metadata.extend(self.cached_metadata(&missing).await);
+        missing.retain(|mint| !metadata.contains_key(mint));
+
+        if missing.is_empty() {
+            return metadata;
+        }
+
+        // Another caller may have resolved some of them while we waited for the locks
+        let keys: Vec<String> = missing.iter().map(|mint| metadata_key(mint)).collect();
+        let _guards = self.locks.lock_all(&keys).await;
+
+        metadata.extend(self.cached_metadata(&missing).await);
+        missing.retain(|mint| !metadata.contains_key(mint));
+
+        if missing.is_empty() {
+            return metadata;
+        }
+
+        let not_fetched: Vec<String> = missing
+            .iter()
+            .filter(|mint| !mint_infos.contains_key(*mint))
+            .cloned()
+            .collect();
+        let fetched = if not_fetched.is_empty() {
+            HashMap::new()
+        } else {
+            fetch_mints(client, &not_fetched).await
+        };
+        let missing_mints: HashMap<&String, &MintInfo> = missing
+            .iter()
+            .filter_map(|mint| {
+                let mint_info = mint_infos.get(mint).or_else(|| fetched.get(mint))?;
+                Some((mint, mint_info))
+            })
+            .collect();
+
+        let mut resolved = resolve_token_2022(client, &missing_mints).await;
+        let token_mints: HashSet<String> =
+            missing_mints.keys().map(|mint| mint.to_string()).collect();
+        missing.retain(|mint| !resolved.contains_key(mint));
+
+        let metaplex = resolve_metaplex(client, &missing).await;
+        let metaplex_failed = metaplex.is_none();
+        resolved.extend(metaplex.unwrap_or_default());
+        missing.retain(|mint| !resolved.contains_key(mint));
+
+        // Accounts of the token programs can't be MPL Core assets
+        let core_candidates: Vec<String> = missing
+            .iter()
+            .filter(|mint| !token_mints.contains(*mint))
+            .cloned()
+            .collect();
+        let mpl_core = resolve_mpl_core(client, &core_candidates).await;
+        let mpl_core_failed = mpl_core.is_none();
+        resolved.extend(mpl_core.unwrap_or_default());
+        missing.retain(|mint| !resolved.contains_key(mint));
+
+        // Tokens without metadata are only remembered when every source could be checked
+        let mut to_cache: Vec<(String, ParsedMetadata)> = resolved.into_iter().collect();
+        for mint in missing {
+            if !metaplex_failed && !mpl_core_failed {
+                to_cache.push((mint, ParsedMetadata::default()));
+            } else {
+                metadata.insert(mint, ParsedMetadata::default());
+            }
+        }
+
+        for (mint, parsed_metadata) in to_cache {
+            if let Ok(value) = serde_json::to_value(&parsed_metadata) {
+                self.cache.set(&metadata_key(&mint), value).await;
+            }
+            metadata.insert(mint, parsed_metadata);
+        }
+
+        metadata
+    }
+
+    async fn cached_metadata(&self, mints: &[String]) -> HashMap<String, ParsedMetadata> {
+        let mut cached = HashMap::new();
+
+        for mint in mints {
+            let value = match self.cache.get(&metadata_key(mint)).await {
+                Some(value) => value,
+                None => continue,
+            };
+
+            if let Ok(parsed_metadata) = serde_json::from_value::<ParsedMetadata>(value) {
+                cached.insert(mint.to_string(), parsed_metadata);
+            }
+        }
+
+        cached
+    }
+
+    /// Fetches or retrieves from cache the image of the token
+    pub async fn fetch_image(
+        &self,
+        client: &SolanaMirrorRpcClient,
+        mint: &str,
+        metadata: &ParsedMetadata,
+    ) -> String {
+        if let Some(image_url) = self.images.predefined_image(mint) {
+            return image_url;
+        }
+        if let Some(logo_uri) = self
+            .registry
+            .get(mint)
+            .and_then(|listed| listed.logo_uri.as_ref())
+        {
+            return self.images.rewrite_uri(logo_uri);
+        }
+
+        let key = image_key(mint);
+        if let Some(image_url) = self.cached_image(&key).await {
+            return image_url;
+        }
+
+        let _guard = self.locks.lock(&key).await;
+        if let Some(image_url) = self.cached_image(&key).await {
+            return image_url;
+        }
+
+        let (image_url, document) = self.images.resolve_document(client, &metadata.uri).await;
+        // The metadata uri was just fetched, keep its JSON for `fetch_off_chain`
+        match document {
+            Some(Document::Json(json)) => {
+                if let Some(value) = serde_json::from_value::<OffChainMetadata>(json)
+                    .ok()
+                    .and_then(|off_chain| serde_json::to_value(off_chain).ok())
+                {
+                    self.cache.set(&off_chain_key(mint), value).await;
+                }
+            }
+            Some(Document::Image) => {
+                self.cache
+                    .set(&off_chain_key(mint), serde_json::Value::Null)
+                    .await
+            }
+            // Not cached, so a gateway error or rate limit doesn't stick until the entry expires
+            None => return image_url,
+        }
+
+        self.cache
+            .set(&key, serde_json::Value::String(image_url.clone()))
+            .await;
+        image_url
+    }
+
+    /// Fetches or retrieves from cache the off-chain JSON metadata of the token, which
+    /// [`MetadataResolver::fetch_image`] already stores. None when the uri is empty or
+    /// doesn't serve JSON
+    pub async fn fetch_off_chain(
+        &self,
+        client: &SolanaMirrorRpcClient,
+        mint: &str,
+        metadata: &ParsedMetadata,
+    ) -> Option<OffChainMetadata> {
+        if metadata.uri.trim().is_empty() {
+            return None;
+        }
+
+        let key = off_chain_key(mint);
+        if let Some(value) = self.cache.get(&key).await {
+            return serde_json::from_value(value).ok();
+        }
+
+        let _guard = self.locks.lock(&key).await;
+        if let Some(value) = self.cache.get(&key).await {
+            return serde_json::from_value(value).ok();
+        }
+
+        let json = client
+            .get_json(&self.images.rewrite_uri(&metadata.uri))
+            .await
+            .ok()?;
+        let off_chain = serde_json::from_value::<OffChainMetadata>(json).ok()?;
+
+        if let Ok(value) = serde_json::to_value(&off_chain) {
+            self.cache.set(&key, value).await;
+        }
+        Some(off_chain)
+    }
+
+    async fn cached_image(&self, key: &str) -> Option<String> {
+        self.cache
+            .get(key)
+            .await
+            .and_then(|value| value.as_str().map(String::from))
+    }
+}
+
+/// Resolves the on-chain metadata, image and off-chain JSON of a token or NFT
+pub async fn get_asset_detail(
+    client: &SolanaMirrorRpcClient,
+    resolver: &MetadataResolver,
+    mint: &str,
+) -> Result<AssetDetail, Error> {
+    Pubkey::from_str(mint).map_err(|_| Error::InvalidAddress)?;
+
+    let metadata = resolver
+        .resolve(client, &[mint.to_string()], &HashMap::new())
+        .await
+        .remove(mint)
+        .unwrap_or_default();
+
+    // The image is resolved first so the off-chain JSON comes from the same fetch
+    let image = resolver.fetch_image(client, mint, &metadata).await;
+    let off_chain = resolver.fetch_off_chain(client, mint, &metadata).await;
+
+    Ok(AssetDetail {
+        mint: mint.to_string(),
+        name: metadata.name,
+        symbol: metadata.symbol,
+        uri: metadata.uri,
+        metadata_source: metadata.source,
+        image,
+        off_chain,
+    })
+}
+
+fn metadata_key(mint: &str) -> String {
+    format!("metadata:{}", mint)
+}
+
+fn off_chain_key(mint: &str) -> String {
+    format!("offchain:{}", mint)
+}
+
+fn image_key(mint: &str) -> String {
+    format!("image:{}", mint)
+}
+
+/// Reads the Token-2022 metadata of the mints, stored on the mint itself or in the account
+/// its metadata pointer points to
+async fn resolve_token_2022(
+    client: &SolanaMirrorRpcClient,
+    mint_infos: &HashMap<&String, &MintInfo>,
+) -> HashMap<String, ParsedMetadata> {
+    let mut resolved: HashMap<String, ParsedMetadata> = HashMap::new();
+    let mut pointers: Vec<(String, Pubkey)> = Vec::new();
+
+    for (mint, mint_info) in mint_infos {
+        let extensions = match mint_info.extensions.as_ref() {
+            Some(extensions) => extensions,
+            None => continue,
+        };
+
+        if let Some(token_metadata) = extensions.token_metadata.as_ref() {
+            resolved.insert(
+                mint.to_string(),
+                ParsedMetadata {
+                    name: token_metadata.name.clone(),
+                    symbol: token_metadata.symbol.clone(),
+                    uri: token_metadata.uri.clone(),
+                    source: MetadataSource::Token2022,
+                    mutable: Some(token_metadata.update_authority.is_some()),
+                },
+            );
+        } else if let Some(address) = extensions
+            .metadata_pointer
+            .as_ref()
+            .and_then(|pointer| pointer.metadata_address.as_ref())
+            .filter(|address| *address != *mint)
+            .and_then(|address| Pubkey::from_str(address).ok())
+        {
+            pointers.push((mint.to_string(), address));
+        }
+    }
+
+    resolved.extend(resolve_metadata_pointers(client, &pointers).await);
+    resolved
+}
+
+/// Loads the accounts metadata pointers point to, either another Token-2022 mint holding the
+/// metadata or a Metaplex metadata account
+async fn resolve_metadata_pointers(
+    client: &SolanaMirrorRpcClient,
+    pointers: &[(String, Pubkey)],
+) -> HashMap<String, ParsedMetadata> {
+    if pointers.is_empty() {
+        return HashMap::new();
+    }
+
+    let pubkeys: Vec<Pubkey> = pointers.iter().map(|(_, address)| *address).collect();
+    // Accounts the node can't parse come back as base64
+    let accounts = match client
+        .get_multiple_accounts(
+            &pubkeys,
+            Some(GetMultipleAccountsConfig {
+                commitment: None,
+                encoding: Some("jsonParsed".to_string()),
+                data_slice: None,
+                min_context_slot: None,
+            }),
+        )
+        .await
+    {
+        Ok(accounts) => accounts,
+        Err(_) => return HashMap::new(),
+    };
+
+    pointers
+        .iter()
+        .zip(accounts)
+        .filter_map(|((mint, address), account)| {
+            let account = account?;
+
+            let parsed_metadata = match account.decode_data() {
+                Some(data) => parse_metadata(&data)?,
+                None => {
+                    let pointed = parse_mint_account(&address.to_string(), &account, None)?;
+                    let token_metadata = pointed.extensions?.token_metadata?;
+                    ParsedMetadata {
+                        name: token_metadata.name,
+                        symbol: token_metadata.symbol,
+                        uri: token_metadata.uri,
+                        source: MetadataSource::Token2022,
+                        mutable: Some(token_metadata.update_authority.is_some()),
+                    }
+                }
+            };
+
+            Some((mint.to_string(), parsed_metadata))
+        })
+        .collect()
+}
+
+/// Loads the Metaplex metadata accounts of the mints. None if the RPC call failed
+async fn resolve_metaplex(
+    client: &SolanaMirrorRpcClient,
+    mints: &[String],
+) -> Option<HashMap<String, ParsedMetadata>> {
+    let mints: Vec<&String> = mints
+        .iter()
+        .filter(|mint| get_metadata_address(mint).is_some())
+        .collect();
+    let metadata_pubkeys: Vec<Pubkey> = mints
+        .iter()
+        .filter_map(|mint| get_metadata_address(mint))
+        .collect();
+
+    let accounts = get_accounts(client, &metadata_pubkeys).await?;
+
+    Some(
+        mints
+            .into_iter()
+            .zip(accounts)
+            .filter_map(|(mint, account)| {
+                let parsed_metadata = parse_metadata(&account?.decode_data()?)?;
+                Some((mint.to_string(), parsed_metadata))
+            })
+            .collect(),
+    )
+}
+
+/// Loads the mints as MPL Core assets or collections. None if the RPC call failed
+async fn resolve_mpl_core(
+    client: &SolanaMirrorRpcClient,
+    mints: &[String],
+) -> Option<HashMap<String, ParsedMetadata>> {
+    let mints: Vec<&String> = mints
+        .iter()
+        .filter(|mint| Pubkey::from_str(mint).is_ok())
+        .collect();
+    let pubkeys: Vec<Pubkey> = mints
+        .iter()
+        .map(|mint| Pubkey::from_str(mint).unwrap())
+        .collect();
+
+    let accounts = get_accounts(client, &pubkeys).await?;
+
+    Some(
+        mints
+            .into_iter()
+            .zip(accounts)
+            .filter_map(|(mint, account)| {
+                let account = account.filter(|account| account.owner == MPL_CORE_PROGRAM_ID)?;
+                Some((
+                    mint.to_string(),
+                    parse_core_account(&account.decode_data()?)?,
+                ))
+            })
+            .collect(),
+    )
+}
+
+/// Fetches the accounts with base64 data. None if the RPC call failed
+async fn get_accounts(
+    client: &SolanaMirrorRpcClient,
+    pubkeys: &[Pubkey],
+) -> Option<Vec<Option<UiAccount>>> {
+    if pubkeys.is_empty() {
+        return Some(Vec::new());
+    }
+
+    client
+        .get_multiple_accounts(
+            pubkeys,
+            Some(GetMultipleAccountsConfig {
+                commitment: None,
+                encoding: Some("base64".to_string()),
+                data_slice: None,
+                min_context_slot: None,
+            }),
+        )
+        .await
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::client::transport::MemoryTransport;
+
+    const MINT: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const URI: &str = "https://example.com/token.json";
+    const IMAGE: &str = "https://example.com/token.png";
+
+    #[tokio::test]
+    async fn failed_image_fetches_are_not_cached() {
+        let transport = Arc::new(MemoryTransport::new());
+        let client = SolanaMirrorRpcClient::with_transport(transport.clone());
+        let resolver = MetadataResolver::new();
+        let metadata = ParsedMetadata {
+            uri: URI.to_string(),
+            ..ParsedMetadata::default()
+        };
+
+        assert_eq!(resolver.fetch_image(&client, MINT, &metadata).await, "");
+
+        transport.insert_json(URI, json!({ "image": IMAGE }));
+        transport.insert_image(IMAGE);
+        assert_eq!(resolver.fetch_image(&client, MINT, &metadata).await, IMAGE);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{balances::accounts::types::ParsedMetadata, utils::clean_string};
+
+use super::types::MetadataSource;
+
+pub const MPL_CORE_PROGRAM_ID: &str = "CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d";
+
+/// Account discriminators of the MPL Core program
+const ASSET_V1: u8 = 1;
+const COLLECTION_V1: u8 = 5;
+
+/// Decodes the name and uri of an MPL Core asset or collection account.
+/// Core assets have no symbol
+pub fn parse_core_account(data: &[u8]) -> Option<ParsedMetadata> {
+    let mut reader = BorshReader { data, offset: 0 };
+
+    match reader.read_u8()? {
+        ASSET_V1 => {
+            // owner
+            reader.skip(32)?;
+            // UpdateAuthority::{None, Address, Collection}
+            if reader.read_u8()? != 0 {
+                reader.skip(32)?;
+            }
+        }
+        COLLECTION_V1 => {
+            // update_authority
+            reader.skip(32)?;
+        }
+        _ => return None,
+    }
+
+    let name = reader.read_string()?;
+    let uri = reader.read_string()?;
+
+    Some(ParsedMetadata {
+        name: clean_string(name),
+        symbol: String::new(),
+        uri: clean_string(uri),
+        source: MetadataSource::MplCore,
+        mutable: None,
+    })
+}
+
+struct BorshReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl BorshReader<'_> {
+    fn read_u8(&mut self) -> Option<u8> {
+        let value = *self.data.get(self.offset)?;
+        self.offset += 1;
+        Some(value)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        if self.offset + len > self.data.len() {
+            return None;
+        }
+        self.offset += len;
+        Some(())
+    }
+
+    fn read_string(&mut self) -> Option<String> {
+        let len_bytes: [u8; 4] = self
+            .data
+            .get(self.offset..self.offset + 4)?
+            .try_into()
+            .ok()?;
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        self.offset += 4;
+
+        let bytes = self.data.get(self.offset..self.offset + len)?;
+        self.offset += len;
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
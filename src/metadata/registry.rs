@@ -0,0 +1,87 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::enums::Error;
+
+/// Chain id of mainnet-beta in the Solana token-list format
+const MAINNET_CHAIN_ID: u64 = 101;
+
+/// A token of a token list, in the Solana token-list / Jupiter strict-list format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenListEntry {
+    pub address: String,
+    #[serde(rename = "chainId")]
+    pub chain_id: Option<u64>,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    #[serde(rename = "logoURI")]
+    pub logo_uri: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub extensions: Option<Value>,
+}
+
+/// `{ "tokens": [...] }` for the Solana token-list, a bare array for Jupiter
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TokenList {
+    Wrapped { tokens: Vec<TokenListEntry> },
+    Bare(Vec<TokenListEntry>),
+}
+
+/// Tokens from a curated list. Listed tokens are considered verified, and the list's
+/// name, symbol and logo take precedence over on-chain metadata. Amounts are always
+/// scaled with the mint's own decimals
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    tokens: HashMap<String, TokenListEntry>,
+}
+
+impl TokenRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a token list file in the Solana token-list or Jupiter strict-list format
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let contents = fs::read_to_string(path).map_err(|e| Error::FetchError(e.to_string()))?;
+        Self::from_json(&contents)
+    }
+
+    /// Parses a token list. Tokens of other chains than mainnet-beta are skipped
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let tokens = match serde_json::from_str::<TokenList>(json)
+            .map_err(|e| Error::ParseError(e.to_string()))?
+        {
+            TokenList::Wrapped { tokens } => tokens,
+            TokenList::Bare(tokens) => tokens,
+        };
+
+        Ok(Self::default().with_tokens(tokens))
+    }
+
+    /// Adds tokens to the registry, replacing the ones already listed
+    pub fn with_tokens(mut self, tokens: Vec<TokenListEntry>) -> Self {
+        for token in tokens {
+            if token.chain_id.unwrap_or(MAINNET_CHAIN_ID) == MAINNET_CHAIN_ID {
+                self.tokens.insert(token.address.clone(), token);
+            }
+        }
+        self
+    }
+
+    pub fn get(&self, mint: &str) -> Option<&TokenListEntry> {
+        self.tokens.get(mint)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// Where the name, symbol and uri of a token were found
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MetadataSource {
+    /// Local override list
+    Override,
+    /// Token list loaded into the registry
+    TokenList,
+    /// Token-2022 metadata extension on the mint
+    Token2022,
+    /// Metaplex token metadata account
+    Metaplex,
+    /// MPL Core asset or collection account
+    MplCore,
+    /// No source had metadata for the token
+    #[default]
+    None,
+}
+
+/// Everything known about a token or NFT, on-chain and off-chain
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct AssetDetail {
+    pub mint: String,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    #[serde(rename = "metadataSource")]
+    pub metadata_source: MetadataSource,
+    /// Resolved image url, empty when none was found
+    pub image: String,
+    /// The JSON document at `uri`, if it could be fetched
+    #[serde(rename = "offChain")]
+    pub off_chain: Option<OffChainMetadata>,
+}
+
+/// Off-chain JSON metadata, following the Metaplex token metadata standard. Malformed
+/// fields are left empty instead of failing the whole document
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct OffChainMetadata {
+    #[serde(default, deserialize_with = "lenient")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub symbol: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub image: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub animation_url: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub external_url: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub attributes: Vec<Attribute>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub collection: Option<OffChainCollection>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub properties: Option<OffChainProperties>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub extensions: Option<OffChainExtensions>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Attribute {
+    #[serde(default)]
+    pub trait_type: Option<String>,
+    /// Strings, numbers or booleans depending on the collection
+    #[serde(default)]
+    pub value: Value,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct OffChainCollection {
+    #[serde(default, deserialize_with = "lenient")]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub family: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct OffChainProperties {
+    #[serde(default, deserialize_with = "lenient")]
+    pub category: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub files: Vec<OffChainFile>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub creators: Vec<OffChainCreator>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct OffChainFile {
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(rename = "type", default)]
+    pub file_type: Option<String>,
+    #[serde(default)]
+    pub cdn: Option<bool>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct OffChainCreator {
+    pub address: String,
+    #[serde(default)]
+    pub share: Option<u8>,
+}
+
+/// Links some fungible tokens publish, like on the Jupiter and Solana token lists
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct OffChainExtensions {
+    #[serde(default, deserialize_with = "lenient")]
+    pub website: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub twitter: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub discord: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub telegram: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub medium: Option<String>,
+    #[serde(default, deserialize_with = "lenient")]
+    pub github: Option<String>,
+}
+
+/// Falls back to the default value when a field has an unexpected shape
+fn lenient<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).unwrap_or_default())
+}
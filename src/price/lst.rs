@@ -0,0 +1,294 @@
+use std::{collections::HashMap, str::FromStr};
+
+use futures::future::join;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    client::{GetMultipleAccountsConfig, SolanaMirrorRpcClient},
+    consts::SOL_ADDRESS,
+};
+
+use super::get_price;
+
+pub const JITOSOL_MINT: &str = "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn";
+pub const JITOSOL_STAKE_POOL: &str = "Jito4APyf642JPZPx3hGc6WWJ8zPKtRbRs4P815Awbb";
+pub const BSOL_MINT: &str = "bSo13r4TkiE4KumL71LsHTPpL2euBYLFx6h9HP3piy1";
+pub const BSOL_STAKE_POOL: &str = "stk9ApL5HeVAwPLr3TLhDXdZS8ptVu7zp6ov8HFDuMi";
+pub const MSOL_MINT: &str = "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So";
+pub const MARINADE_STATE: &str = "8szGkuLTAux9XMgZ2vtY39jVSowEcpBfFfD8hXSEqdGC";
+
+// SPL stake pool layout: account type, manager, staker, deposit authority, withdraw bump,
+// validator list, reserve stake, pool mint, manager fee account, token program
+const STAKE_POOL_ACCOUNT_TYPE: u8 = 1;
+const STAKE_POOL_MINT_OFFSET: usize = 162;
+const STAKE_POOL_TOTAL_LAMPORTS_OFFSET: usize = 258;
+const STAKE_POOL_TOKEN_SUPPLY_OFFSET: usize = 266;
+
+// Marinade state layout: anchor discriminator, mSOL mint, ... then the mSOL price
+// as a fixed point number with 32 fractional bits
+const MARINADE_MINT_OFFSET: usize = 8;
+const MARINADE_MSOL_PRICE_OFFSET: usize = 512;
+const MARINADE_PRICE_DENOMINATOR: f64 = 4_294_967_296.0;
+
+/// Rates outside these bounds come from a misread or broken account, those tokens
+/// are priced from swap quotes instead
+const MIN_RATE: f64 = 0.5;
+const MAX_RATE: f64 = 5.0;
+
+/// On-chain account holding the exchange rate of a liquid staking token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LstSource {
+    /// SPL stake pool, the rate is `total_lamports / pool_token_supply`
+    StakePool(String),
+    /// Marinade state, the rate is `msol_price / 2^32`
+    Marinade(String),
+}
+
+/// Values liquid staking tokens from their stake pool state instead of swap quotes,
+/// which break on thin liquidity
+#[derive(Debug, Clone)]
+pub struct LstOracle {
+    sources: HashMap<String, LstSource>,
+}
+
+impl Default for LstOracle {
+    fn default() -> Self {
+        Self {
+            sources: HashMap::from([
+                (
+                    JITOSOL_MINT.to_string(),
+                    LstSource::StakePool(JITOSOL_STAKE_POOL.to_string()),
+                ),
+                (
+                    BSOL_MINT.to_string(),
+                    LstSource::StakePool(BSOL_STAKE_POOL.to_string()),
+                ),
+                (
+                    MSOL_MINT.to_string(),
+                    LstSource::Marinade(MARINADE_STATE.to_string()),
+                ),
+            ]),
+        }
+    }
+}
+
+impl LstOracle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Values the mint from the given SPL stake pool
+    pub fn with_stake_pool(mut self, mint: &str, pool: &str) -> Self {
+        self.sources
+            .insert(mint.to_string(), LstSource::StakePool(pool.to_string()));
+        self
+    }
+
+    /// Values the mint from the given source
+    pub fn with_source(mut self, mint: &str, source: LstSource) -> Self {
+        self.sources.insert(mint.to_string(), source);
+        self
+    }
+
+    /// Returns the SOL each token is worth, for the mints with a known source.
+    /// Sources that can't be fetched or decoded, or give an implausible rate, are left out
+    pub async fn get_rates(
+        &self,
+        client: &SolanaMirrorRpcClient,
+        mints: &[String],
+    ) -> HashMap<String, f64> {
+        let mut sources: Vec<(&String, &LstSource, Pubkey)> = Vec::new();
+        for mint in mints {
+            if sources.iter().any(|(known, _, _)| *known == mint) {
+                continue;
+            }
+            if let Some(source) = self.sources.get(mint) {
+                let address = match source {
+                    LstSource::StakePool(address) | LstSource::Marinade(address) => address,
+                };
+                if let Ok(pubkey) = Pubkey::from_str(address) {
+                    sources.push((mint, source, pubkey));
+                }
+            }
+        }
+
+        if sources.is_empty() {
+            return HashMap::new();
+        }
+
+        let pubkeys: Vec<Pubkey> = sources.iter().map(|(_, _, pubkey)| *pubkey).collect();
+        let accounts = match client
+            .get_multiple_accounts(
+                &pubkeys,
+                Some(GetMultipleAccountsConfig {
+                    commitment: None,
+                    encoding: Some("base64".to_string()),
+                    data_slice: None,
+                    min_context_slot: None,
+                }),
+            )
+            .await
+        {
+            Ok(accounts) => accounts,
+            Err(_) => return HashMap::new(),
+        };
+
+        sources
+            .into_iter()
+            .zip(accounts)
+            .filter_map(|((mint, source, _), account)| {
+                let data = account?.decode_data()?;
+                let rate = match source {
+                    LstSource::StakePool(_) => parse_stake_pool_rate(&data, mint)?,
+                    LstSource::Marinade(_) => parse_marinade_rate(&data, mint)?,
+                };
+                (MIN_RATE..=MAX_RATE)
+                    .contains(&rate)
+                    .then(|| (mint.to_string(), rate))
+            })
+            .collect()
+    }
+
+    /// Returns the USD price of the mints with a known source, from their rate and the price
+    /// of SOL. No request is made when none of the mints is a known LST
+    pub async fn get_prices(
+        &self,
+        client: &SolanaMirrorRpcClient,
+        mints: &[String],
+    ) -> HashMap<String, f64> {
+        if !mints.iter().any(|mint| self.sources.contains_key(mint)) {
+            return HashMap::new();
+        }
+
+        let (rates, sol_price) = join(
+            self.get_rates(client, mints),
+            get_price(client, Pubkey::from_str(SOL_ADDRESS).unwrap(), Some(9)),
+        )
+        .await;
+
+        match sol_price {
+            Some(sol_price) => rates
+                .into_iter()
+                .map(|(mint, rate)| (mint, rate * sol_price))
+                .collect(),
+            None => HashMap::new(),
+        }
+    }
+}
+
+/// SOL per pool token. None if the account isn't the stake pool of the mint
+fn parse_stake_pool_rate(data: &[u8], mint: &str) -> Option<f64> {
+    if *data.first()? != STAKE_POOL_ACCOUNT_TYPE
+        || read_pubkey(data, STAKE_POOL_MINT_OFFSET)? != mint
+    {
+        return None;
+    }
+
+    let total_lamports = read_u64(data, STAKE_POOL_TOTAL_LAMPORTS_OFFSET)?;
+    let pool_token_supply = read_u64(data, STAKE_POOL_TOKEN_SUPPLY_OFFSET)?;
+    if pool_token_supply == 0 {
+        return None;
+    }
+
+    Some(total_lamports as f64 / pool_token_supply as f64)
+}
+
+/// SOL per mSOL. None if the account isn't the Marinade state of the mint
+fn parse_marinade_rate(data: &[u8], mint: &str) -> Option<f64> {
+    if read_pubkey(data, MARINADE_MINT_OFFSET)? != mint {
+        return None;
+    }
+
+    let msol_price = read_u64(data, MARINADE_MSOL_PRICE_OFFSET)?;
+    Some(msol_price as f64 / MARINADE_PRICE_DENOMINATOR)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset + 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Option<String> {
+    let bytes: [u8; 32] = data.get(offset..offset + 32)?.try_into().ok()?;
+    Some(Pubkey::new_from_array(bytes).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use base64::Engine;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::client::transport::MemoryTransport;
+
+    const STAKE_POOL_PROGRAM: &str = "SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy";
+    const MARINADE_PROGRAM: &str = "MarBmsSgKXdrN1egZf5sqe1TMai9K1rChYNDJgjq7aD";
+
+    /// Stake pool account with the given pool mint, total lamports and pool token supply
+    fn stake_pool(mint: &str, total_lamports: u64, supply: u64) -> Vec<u8> {
+        let mut data = vec![0; 611];
+        data[0] = STAKE_POOL_ACCOUNT_TYPE;
+        data[STAKE_POOL_MINT_OFFSET..STAKE_POOL_MINT_OFFSET + 32]
+            .copy_from_slice(&Pubkey::from_str(mint).unwrap().to_bytes());
+        data[STAKE_POOL_TOTAL_LAMPORTS_OFFSET..STAKE_POOL_TOTAL_LAMPORTS_OFFSET + 8]
+            .copy_from_slice(&total_lamports.to_le_bytes());
+        data[STAKE_POOL_TOKEN_SUPPLY_OFFSET..STAKE_POOL_TOKEN_SUPPLY_OFFSET + 8]
+            .copy_from_slice(&supply.to_le_bytes());
+        data
+    }
+
+    /// Marinade state with the given mSOL price, a fixed point number with 32 fractional bits
+    fn marinade_state(msol_price: u64) -> Vec<u8> {
+        let mut data = vec![0; 1_000];
+        data[MARINADE_MINT_OFFSET..MARINADE_MINT_OFFSET + 32]
+            .copy_from_slice(&Pubkey::from_str(MSOL_MINT).unwrap().to_bytes());
+        data[MARINADE_MSOL_PRICE_OFFSET..MARINADE_MSOL_PRICE_OFFSET + 8]
+            .copy_from_slice(&msol_price.to_le_bytes());
+        data
+    }
+
+    fn account(data: &[u8], owner: &str) -> Value {
+        json!({
+            "data": [base64::prelude::BASE64_STANDARD.encode(data), "base64"],
+            "executable": false,
+            "lamports": 1_000_000_000,
+            "owner": owner,
+            "rentEpoch": 0,
+            "space": data.len()
+        })
+    }
+
+    #[tokio::test]
+    async fn decodes_rates_from_pool_accounts() {
+        let jitosol_pool = stake_pool(JITOSOL_MINT, 1_150_000_000, 1_000_000_000);
+        let msol_state = marinade_state(5_368_709_120);
+        // 12 SOL per token can only come from a broken account
+        let bsol_pool = stake_pool(BSOL_MINT, 12_000_000_000, 1_000_000_000);
+
+        let transport = MemoryTransport::new().with_response(
+            "getMultipleAccounts",
+            json!([
+                [JITOSOL_STAKE_POOL, MARINADE_STATE, BSOL_STAKE_POOL],
+                { "commitment": null, "encoding": "base64", "dataSlice": null, "minContextSlot": null }
+            ]),
+            json!({
+                "context": { "apiVersion": "2.0.0", "slot": 1 },
+                "value": [
+                    account(&jitosol_pool, STAKE_POOL_PROGRAM),
+                    account(&msol_state, MARINADE_PROGRAM),
+                    account(&bsol_pool, STAKE_POOL_PROGRAM),
+                ]
+            }),
+        );
+        let client = SolanaMirrorRpcClient::with_transport(Arc::new(transport));
+
+        let mints = [JITOSOL_MINT, MSOL_MINT, BSOL_MINT].map(String::from);
+        let rates = LstOracle::new().get_rates(&client, &mints).await;
+
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[JITOSOL_MINT], 1.15);
+        assert_eq!(rates[MSOL_MINT], 1.25);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::client::GetDecimalsConfig;
+use crate::client::SolanaMirrorRpcClient;
+use crate::consts::{JUPITER_QUOTE_API, USDC_ADDRESS};
+use crate::enums::Error;
+use solana_sdk::pubkey::Pubkey;
+
+pub use lst::{LstOracle, LstSource};
+
+pub mod lst;
+
+/// Jupiter error codes meaning the token can't be swapped to USDC at all
+const NO_ROUTE_ERRORS: [&str; 2] = ["COULD_NOT_FIND_ANY_ROUTE", "TOKEN_NOT_TRADABLE"];
+
+/// Gets the price of the mint against USDC
+/// Lets the caller pass the decimals beforehand. If they're not passed, they will be fetched
+pub async fn get_price(
+    client: &SolanaMirrorRpcClient,
+    token: Pubkey,
+    decimals: Option<u8>,
+) -> Option<f64> {
+    get_quote(client, token, decimals).await.ok().flatten()
+}
+
+/// Like [`get_price`], but tells a definitive "no route to USDC" answer (`Ok(None)`) apart
+/// from a quote that couldn't be fetched (`Err`)
+pub async fn get_quote(
+    client: &SolanaMirrorRpcClient,
+    token: Pubkey,
+    decimals: Option<u8>,
+) -> Result<Option<f64>, Error> {
+    // If it's comparing USDC to itself return 1
+    if token.to_string() == *USDC_ADDRESS.to_string() {
+        return Ok(Some(1.0));
+    }
+
+    let decimals_b = 6;
+    let decimals_a = match decimals {
+        Some(d) => d,
+        None => {
+            let response = client
+                .get_decimals(
+                    &token,
+                    Some(GetDecimalsConfig {
+                        commitment: Some("confirmed".to_string()),
+                    }),
+                )
+                .await;
+
+            response?.result.value.decimals
+        }
+    };
+
+    let amount = 10_u64.pow(decimals_a as u32);
+
+    // Goes through the client's transport so quotes can be recorded and replayed
+    let url = format!(
+        "{}/quote?inputMint={}&outputMint={}&amount={}&slippageBps=0",
+        JUPITER_QUOTE_API, token, USDC_ADDRESS, amount
+    );
+
+    let quote = match client.get_json(&url).await {
+        Ok(quote) => quote,
+        // Jupiter answers 400 when there is no route, with the error code in the body.
+        // Any other 400, e.g. a bad parameter, says nothing about the token
+        Err(Error::HttpError {
+            status: 400,
+            body: Some(body),
+            ..
+        }) if is_no_route(&serde_json::from_str(&body).unwrap_or_default()) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    if is_no_route(&quote) {
+        return Ok(None);
+    }
+    if let Some(error_code) = quote["errorCode"].as_str() {
+        return Err(Error::FetchError(error_code.to_string()));
+    }
+
+    let out_amount = quote["outAmount"]
+        .as_str()
+        .and_then(|out_amount| out_amount.parse::<u64>().ok())
+        .ok_or_else(|| Error::ParseError("Missing outAmount in quote".to_string()))?;
+    Ok(Some(out_amount as f64 / 10_f64.powi(decimals_b)))
+}
+
+/// Whether a Jupiter response says the token can't be swapped to USDC
+fn is_no_route(response: &serde_json::Value) -> bool {
+    response["errorCode"]
+        .as_str()
+        .is_some_and(|error_code| NO_ROUTE_ERRORS.contains(&error_code))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, sync::Arc};
+
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::client::transport::{Document, Transport};
+
+    const MINT: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    /// Answers every quote with a 400 and the given body
+    struct BadRequestTransport {
+        body: Value,
+    }
+
+    #[async_trait]
+    impl Transport for BadRequestTransport {
+        async fn send(&self, _request: &Value) -> Result<Value, Error> {
+            Err(Error::FetchError("Only quotes are served".to_string()))
+        }
+
+        async fn send_batch(&self, _requests: &[Value]) -> Result<Value, Error> {
+            Err(Error::FetchError("Only quotes are served".to_string()))
+        }
+
+        async fn get_json(&self, _url: &str) -> Result<Value, Error> {
+            Err(Error::HttpError {
+                status: 400,
+                retry_after: None,
+                body: Some(self.body.to_string()),
+            })
+        }
+
+        async fn get_document(&self, url: &str) -> Result<Document, Error> {
+            Err(Error::FetchError(format!("No document for {}", url)))
+        }
+
+        fn endpoint(&self) -> String {
+            "bad-request".to_string()
+        }
+    }
+
+    async fn quote_with_400(body: Value) -> Result<Option<f64>, Error> {
+        let client = SolanaMirrorRpcClient::with_transport(Arc::new(BadRequestTransport { body }));
+        get_quote(&client, Pubkey::from_str(MINT).unwrap(), Some(6)).await
+    }
+
+    #[tokio::test]
+    async fn only_no_route_errors_mean_no_route() {
+        let no_route = quote_with_400(json!({ "errorCode": "COULD_NOT_FIND_ANY_ROUTE" })).await;
+        assert!(matches!(no_route, Ok(None)));
+
+        let bad_param = quote_with_400(json!({ "error": "amount must be positive" })).await;
+        assert!(matches!(
+            bad_param,
+            Err(Error::HttpError { status: 400, .. })
+        ));
+    }
+}
@@ -1,11 +1,35 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use crate::balances::accounts::{get_parsed_accounts, ParsedAta};
+use crate::balances::accounts::{get_parsed_accounts_with_mints, ParsedAta};
+use crate::balances::audit::{get_token_audit, TokenAudit};
 use crate::balances::dapps::raydium::{get_raydium_positions, ParsedPosition};
+use crate::balances::mints::{fetch_largest_accounts, fetch_mints, MintInfo};
+use crate::balances::nfts::{get_nfts, NftCollection};
+use crate::balances::reclaim::{
+    build_close_transactions, get_rent_reclaim_report, ClosableAccount, CloseTransaction,
+    RentReclaimReport,
+};
+use crate::balances::risk::{
+    attach_mint_risk_profiles, get_mint_risk_profiles, risk_profile_mints, MintRiskProfile,
+};
+use crate::balances::sol::{
+    build_sol_breakdown, get_sol_breakdown, get_sol_holdings, SolBreakdown,
+};
+use crate::balances::spam::SpamClassifier;
+use crate::balances::stake::{
+    get_stake_accounts, get_unvalued_stake_accounts, with_price, ParsedStakeAccount,
+};
+use crate::balances::types::BalancesResponse;
 use crate::chart::{get_chart_data, ChartData, Timeframe};
-use crate::client::SolanaMirrorRpcClient;
+use crate::client::{
+    limiter::RateLimit, retry::RetryPolicy, transport::Transport, SolanaMirrorRpcClient,
+};
+use crate::consts::SYSTEM_PROGRAM_ID;
 use crate::enums::Error;
+use crate::metadata::{get_asset_detail, AssetDetail, Cache, MetadataResolver};
+use crate::price::LstOracle;
 use crate::transactions::{get_parsed_transactions, TransactionResponse};
+use futures::future::join3;
 use reqwest::Client;
 use solana_sdk::pubkey::Pubkey;
 
@@ -15,6 +39,14 @@ pub struct SolanaMirror {
     watch: Pubkey,
     /// Client instance for making RPC calls
     client: SolanaMirrorRpcClient,
+    /// Resolves token names, symbols and uris
+    metadata: MetadataResolver,
+    /// Classifies spam holdings, disabled by default
+    spam: SpamClassifier,
+    /// Whether token accounts come with the risk profile of their mint
+    risk_profiles: bool,
+    /// Values liquid staking tokens from their stake pool
+    lst: LstOracle,
 }
 
 impl SolanaMirror {
@@ -29,9 +61,113 @@ impl SolanaMirror {
         Self {
             watch,
             client: SolanaMirrorRpcClient::new(http_client.clone(), rpc_url),
+            metadata: MetadataResolver::default(),
+            spam: SpamClassifier::default(),
+            risk_profiles: false,
+            lst: LstOracle::default(),
         }
     }
 
+    /// Creates a new instance of SolanaMirror that sends its RPC calls through a custom transport
+    ///
+    /// # Arguments
+    /// * `watch` - The Solana address to watch
+    /// * `transport` - The transport carrying the JSON-RPC requests
+    pub fn with_transport(watch: Pubkey, transport: Arc<dyn Transport>) -> Self {
+        Self {
+            watch,
+            client: SolanaMirrorRpcClient::with_transport(transport),
+            metadata: MetadataResolver::default(),
+            spam: SpamClassifier::default(),
+            risk_profiles: false,
+            lst: LstOracle::default(),
+        }
+    }
+
+    /// Replaces the policy used to retry failed RPC and price requests
+    ///
+    /// # Arguments
+    /// * `retry_policy` - Attempts, backoff and jitter settings to use
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.client = self.client.with_retry_policy(retry_policy);
+        self
+    }
+
+    /// Caps how many requests go in a single JSON-RPC batch, bigger batches are split
+    ///
+    /// # Arguments
+    /// * `max_batch_size` - Maximum number of requests per batch
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.client = self.client.with_max_batch_size(max_batch_size);
+        self
+    }
+
+    /// Throttles the RPC endpoint (and any HTTP API without its own limit)
+    ///
+    /// # Arguments
+    /// * `limit` - Requests per second, burst and max in-flight requests
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.client = self.client.with_rate_limit(limit);
+        self
+    }
+
+    /// Throttles a single endpoint with its own budget
+    ///
+    /// # Arguments
+    /// * `endpoint` - The RPC URL, or the host of an HTTP API like `quote-api.jup.ag`
+    /// * `limit` - Requests per second, burst and max in-flight requests
+    pub fn with_endpoint_rate_limit(mut self, endpoint: &str, limit: RateLimit) -> Self {
+        self.client = self.client.with_endpoint_rate_limit(endpoint, limit);
+        self
+    }
+
+    /// Replaces the resolver used to find token metadata, e.g. to add local overrides
+    ///
+    /// # Arguments
+    /// * `resolver` - The metadata resolver
+    pub fn with_metadata_resolver(mut self, resolver: MetadataResolver) -> Self {
+        self.metadata = resolver;
+        self
+    }
+
+    /// Replaces the cache holding resolved metadata and images, e.g. with a `FileCache`
+    /// shared between processes
+    ///
+    /// # Arguments
+    /// * `cache` - The cache implementation
+    pub fn with_cache(mut self, cache: Arc<dyn Cache>) -> Self {
+        self.metadata = self.metadata.with_cache(cache);
+        self
+    }
+
+    /// Classifies the token accounts as spam or not, to flag or hide them
+    ///
+    /// # Arguments
+    /// * `classifier` - The spam classifier, with its policy and threshold
+    pub fn with_spam_classifier(mut self, classifier: SpamClassifier) -> Self {
+        self.spam = classifier;
+        self
+    }
+
+    /// Attaches the risk profile of their mint to the token accounts. Costs one
+    /// `getTokenLargestAccounts` call per mint, so it is disabled by default
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether to build the risk profiles
+    pub fn with_mint_risk_profiles(mut self, enabled: bool) -> Self {
+        self.risk_profiles = enabled;
+        self
+    }
+
+    /// Replaces the stake pools liquid staking tokens are valued from, e.g. to add an LST
+    ///
+    /// # Arguments
+    /// * `lst` - The LST oracle, with the stake pool of each mint
+    pub fn with_lst_oracle(mut self, lst: LstOracle) -> Self {
+        self.lst = lst;
+        self
+    }
+
     /// Returns the address being watched
     pub fn get_watch_address(&self) -> Pubkey {
         self.watch
@@ -53,10 +189,42 @@ impl SolanaMirror {
         &self,
         show_apps: Option<bool>,
     ) -> Result<(Vec<ParsedAta>, Option<Vec<ParsedPosition>>), Error> {
-        let accounts = get_parsed_accounts(&self.client, &self.watch).await?;
+        let parsed =
+            get_parsed_accounts_with_mints(&self.client, &self.metadata, &self.lst, &self.watch)
+                .await?;
+
+        // Spam and risk profiles share the largest accounts of the mints
+        let mut largest_mints = self
+            .spam
+            .holder_count_mints(&parsed.accounts, &parsed.mint_infos);
+        if self.risk_profiles {
+            largest_mints.extend(risk_profile_mints(&parsed.accounts));
+        }
+        largest_mints.sort();
+        largest_mints.dedup();
+        let largest_accounts = fetch_largest_accounts(&self.client, &largest_mints).await;
+
+        let accounts = self.spam.apply(
+            &self.metadata,
+            &parsed.mint_infos,
+            &largest_accounts,
+            parsed.accounts,
+        );
+        let accounts = if self.risk_profiles {
+            attach_mint_risk_profiles(
+                &self.client,
+                accounts,
+                &parsed.mint_infos,
+                &parsed.metadata,
+                &largest_accounts,
+            )
+            .await
+        } else {
+            accounts
+        };
 
         let positions = if show_apps.unwrap_or(false) {
-            Some(get_raydium_positions(&self.client, &self.watch).await?)
+            Some(get_raydium_positions(&self.client, &self.metadata, &self.lst, &self.watch).await?)
         } else {
             None
         };
@@ -64,6 +232,166 @@ impl SolanaMirror {
         Ok((accounts, positions))
     }
 
+    /// Gets the portfolio of the watched address: token accounts, optionally dapp positions,
+    /// stake accounts and the SOL breakdown, and the total USD value of all of them
+    ///
+    /// # Arguments
+    /// * `show_apps` - Whether to include dapp positions
+    /// * `show_stake` - Whether to include native stake accounts. They are left out when
+    ///   the node doesn't serve `getProgramAccounts`
+    /// * `show_sol` - Whether to include the native, wrapped, rent locked and staked SOL
+    pub async fn get_balances(
+        &self,
+        show_apps: Option<bool>,
+        show_stake: Option<bool>,
+        show_sol: Option<bool>,
+    ) -> Result<BalancesResponse, Error> {
+        let show_stake = show_stake.unwrap_or(false);
+        let show_sol = show_sol.unwrap_or(false);
+
+        // The breakdown is built from the same stake accounts
+        let (token_accounts, stake, sol_holdings) = join3(
+            self.get_token_accounts(show_apps),
+            async {
+                if show_stake || show_sol {
+                    get_unvalued_stake_accounts(&self.client, &self.watch)
+                        .await
+                        .ok()
+                } else {
+                    None
+                }
+            },
+            async {
+                if show_sol {
+                    Some(get_sol_holdings(&self.client, &self.watch).await)
+                } else {
+                    None
+                }
+            },
+        )
+        .await;
+        let (accounts, raydium) = token_accounts?;
+
+        // The native SOL account was priced along with the tokens
+        let sol_price = accounts
+            .iter()
+            .find(|account| account.token_program == SYSTEM_PROGRAM_ID)
+            .and_then(|account| account.price);
+        let stake = stake.map(|stake| with_price(stake, sol_price));
+
+        let sol = match sol_holdings {
+            Some(holdings) => {
+                let (native, token_accounts) = holdings?;
+                Some(build_sol_breakdown(
+                    &self.watch,
+                    native,
+                    &token_accounts,
+                    stake.as_deref(),
+                    sol_price,
+                ))
+            }
+            None => None,
+        };
+        let stake = stake.filter(|_| show_stake);
+
+        // Stake accounts the address only delegates belong to someone else
+        let watch = self.watch.to_string();
+        let total_value_usd = accounts
+            .iter()
+            .filter_map(|account| account.price.map(|price| price * account.balance.formatted))
+            .chain(
+                raydium
+                    .iter()
+                    .flatten()
+                    .filter_map(|position| position.total_value_usd),
+            )
+            .chain(
+                stake
+                    .iter()
+                    .flatten()
+                    .filter(|stake| stake.withdrawer == watch)
+                    .filter_map(|stake| stake.total_value_usd),
+            )
+            .sum();
+
+        Ok(BalancesResponse {
+            accounts,
+            raydium,
+            stake,
+            sol,
+            total_value_usd,
+        })
+    }
+
+    /// Gets the native stake accounts the watched address is staker or withdrawer of,
+    /// with their delegation, activation, lockup and value in SOL and USD
+    pub async fn get_stake_accounts(&self) -> Result<Vec<ParsedStakeAccount>, Error> {
+        get_stake_accounts(&self.client, &self.watch).await
+    }
+
+    /// Gets every lamport the watched address owns: native balance, wrapped SOL, rent locked
+    /// in token accounts and staked SOL, with their total in SOL and USD
+    pub async fn get_sol_breakdown(&self) -> Result<SolBreakdown, Error> {
+        get_sol_breakdown(&self.client, &self.watch).await
+    }
+
+    /// Audits the token accounts of the watched address: who can spend from them through
+    /// a delegate approval, and which ones are frozen
+    pub async fn get_token_audit(&self) -> Result<TokenAudit, Error> {
+        get_token_audit(&self.client, &self.watch).await
+    }
+
+    /// Lists the empty token accounts of the watched address that can be closed, with the
+    /// rent each one gives back
+    pub async fn get_rent_reclaim_report(&self) -> Result<RentReclaimReport, Error> {
+        get_rent_reclaim_report(&self.client, &self.watch).await
+    }
+
+    /// Builds unsigned transactions closing the accounts, for the watched address to sign
+    ///
+    /// # Arguments
+    /// * `accounts` - Accounts to close, usually taken from the rent reclaim report
+    pub async fn build_close_transactions(
+        &self,
+        accounts: &[ClosableAccount],
+    ) -> Result<Vec<CloseTransaction>, Error> {
+        build_close_transactions(&self.client, &self.watch, accounts).await
+    }
+
+    /// Gets the NFTs and pNFTs held by the watched address, grouped by verified collection
+    pub async fn get_nfts(&self) -> Result<Vec<NftCollection>, Error> {
+        get_nfts(&self.client, &self.metadata, &self.watch).await
+    }
+
+    /// Fetches mint accounts, with their supply, authorities and Token-2022 extensions
+    ///
+    /// # Arguments
+    /// * `mints` - Mint addresses, the ones that don't exist are left out
+    pub async fn get_mints(&self, mints: &[String]) -> HashMap<String, MintInfo> {
+        fetch_mints(&self.client, mints).await
+    }
+
+    /// Builds the risk profile of each mint: authorities, supply, concentration of the
+    /// largest holders and whether the metadata is mutable
+    ///
+    /// # Arguments
+    /// * `mints` - Mint addresses, the ones that don't exist are left out
+    pub async fn get_mint_risk_profiles(
+        &self,
+        mints: &[String],
+    ) -> HashMap<String, MintRiskProfile> {
+        get_mint_risk_profiles(&self.client, mints).await
+    }
+
+    /// Fetches the details of a token or NFT: on-chain metadata, image and the off-chain
+    /// JSON (description, attributes, collection, files, creators, links)
+    ///
+    /// # Arguments
+    /// * `mint` - The mint address, or the asset address for MPL Core
+    pub async fn get_asset_detail(&self, mint: &str) -> Result<AssetDetail, Error> {
+        get_asset_detail(&self.client, &self.metadata, mint).await
+    }
+
     /// Fetches and parses transactions for the watched address
     ///
     /// # Arguments
@@ -88,3 +416,134 @@ impl SolanaMirror {
         get_chart_data(&self.client, &self.watch, range, timeframe).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json::{json, Value};
+    use spl_token::id as spl_token_id;
+
+    use super::*;
+    use crate::{
+        client::{transport::MemoryTransport, GetProgramAccountsConfig, RpcFilter},
+        consts::{
+            JUPITER_QUOTE_API, SOL_ADDRESS, STAKE_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, USDC_ADDRESS,
+        },
+    };
+
+    const OWNER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const OTHER: &str = "3emsAVdmGKERbHjmGfQ6oZ1e35dkf5iYcS6U4CPKFVaa";
+    const VOTER: &str = "CertusDeBmqN8ZawdkxK5kFGMwBXdudvWHYwtNgNhvLu";
+
+    fn context() -> Value {
+        json!({ "apiVersion": "2.0.0", "slot": 1 })
+    }
+
+    fn stake_params(offset: usize) -> Value {
+        let config = GetProgramAccountsConfig::default()
+            .with_encoding("jsonParsed")
+            .with_filter(RpcFilter::data_size(200))
+            .with_filter(RpcFilter::memcmp_pubkey(
+                offset,
+                &Pubkey::from_str(OWNER).unwrap(),
+            ));
+        json!([STAKE_PROGRAM_ID, config])
+    }
+
+    fn stake_account(address: &str, withdrawer: &str, lamports: u64) -> Value {
+        json!({
+            "pubkey": address,
+            "account": {
+                "data": {
+                    "parsed": {
+                        "type": "delegated",
+                        "info": {
+                            "meta": {
+                                "rentExemptReserve": "2282880",
+                                "authorized": { "staker": OWNER, "withdrawer": withdrawer },
+                                "lockup": { "unixTimestamp": 0, "epoch": 0, "custodian": OTHER }
+                            },
+                            "stake": {
+                                "delegation": {
+                                    "voter": VOTER,
+                                    "stake": (lamports - 2282880).to_string(),
+                                    "activationEpoch": "10",
+                                    "deactivationEpoch": u64::MAX.to_string(),
+                                    "warmupCooldownRate": 0.25
+                                },
+                                "creditsObserved": 0
+                            }
+                        }
+                    },
+                    "program": "stake",
+                    "space": 200
+                },
+                "executable": false,
+                "lamports": lamports,
+                "owner": STAKE_PROGRAM_ID,
+                "rentEpoch": 0,
+                "space": 200
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn stake_withdrawn_by_someone_else_is_left_out_of_the_total() {
+        let own = stake_account(
+            "Jito4APyf642JPZPx3hGc6WWJ8zPKtRbRs4P815Awbb",
+            OWNER,
+            2_000_000_000,
+        );
+        let delegated = stake_account(
+            "stk9ApL5HeVAwPLr3TLhDXdZS8ptVu7zp6ov8HFDuMi",
+            OTHER,
+            3_000_000_000,
+        );
+
+        let transport = MemoryTransport::new()
+            .with_response(
+                "getTokenAccountsByOwner",
+                json!([OWNER, { "programId": spl_token_id().to_string() }, { "commitment": null, "minContextSlot": null, "dataSlice": null, "encoding": "jsonParsed" }]),
+                json!({ "context": context(), "value": [] }),
+            )
+            .with_response(
+                "getTokenAccountsByOwner",
+                json!([OWNER, { "programId": TOKEN_2022_PROGRAM_ID }, { "commitment": null, "minContextSlot": null, "dataSlice": null, "encoding": "jsonParsed" }]),
+                json!({ "context": context(), "value": [] }),
+            )
+            .with_response(
+                "getBalance",
+                json!([OWNER, null]),
+                json!({ "context": context(), "value": 0 }),
+            )
+            .with_response("getProgramAccounts", stake_params(12), json!([own, delegated]))
+            .with_response("getProgramAccounts", stake_params(44), json!([own]))
+            .with_response(
+                "getEpochInfo",
+                json!([null]),
+                json!({ "absoluteSlot": 1, "blockHeight": 1, "epoch": 20, "slotIndex": 1, "slotsInEpoch": 432000, "transactionCount": 1 }),
+            );
+        transport.insert_json(
+            &format!(
+                "{}/quote?inputMint={}&outputMint={}&amount=1000000000&slippageBps=0",
+                JUPITER_QUOTE_API, SOL_ADDRESS, USDC_ADDRESS
+            ),
+            json!({ "outAmount": "100000000" }),
+        );
+        let mirror =
+            SolanaMirror::with_transport(Pubkey::from_str(OWNER).unwrap(), Arc::new(transport));
+
+        let balances = mirror.get_balances(None, Some(true), None).await.unwrap();
+
+        assert_eq!(balances.stake.as_ref().map(Vec::len), Some(2));
+        assert_eq!(balances.total_value_usd, 200.0);
+        assert!(balances.sol.is_none());
+
+        let balances = mirror.get_balances(None, None, Some(true)).await.unwrap();
+        let sol = balances.sol.unwrap();
+        assert!(balances.stake.is_none());
+        assert_eq!(sol.staked.map(|staked| staked.formatted), Some(2.0));
+        assert_eq!(sol.total_value_usd, Some(200.0));
+    }
+}
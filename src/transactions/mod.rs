@@ -13,7 +13,7 @@ use crate::{
     enums::Error,
     transactions::types::{BalanceChange, ParsedTransaction},
     types::FormattedAmount,
-    utils::{create_batches, parse_page},
+    utils::parse_page,
 };
 
 pub use self::types::TransactionResponse;
@@ -29,9 +29,10 @@ pub async fn get_parsed_transactions(
         Err(e) => return Err(Error::ParseError(e.to_string())),
     };
 
-    let signatures = get_signatures(&client, &address).await?;
+    let signatures = get_signatures(client, address).await?;
 
-    let batches = match page {
+    // The client splits these into batches of its configured size
+    let page_signatures = match page {
         Some(p) => {
             if p.start_idx >= signatures.len() {
                 return Ok(TransactionResponse {
@@ -39,34 +40,40 @@ pub async fn get_parsed_transactions(
                     transactions: Vec::<ParsedTransaction>::new(),
                 });
             } else if p.end_idx >= signatures.len() {
-                vec![signatures[p.start_idx..].to_vec()]
+                &signatures[p.start_idx..]
             } else {
-                vec![signatures[p.start_idx..p.end_idx].to_vec()]
+                &signatures[p.start_idx..p.end_idx]
             }
         }
-        None => create_batches(&signatures, 900, None),
+        None => &signatures[..],
     };
 
-    let mut txs: Vec<Transaction> = Vec::new();
+    let transactions: Vec<Result<crate::client::GetTransactionResponse, Error>> = client
+        .get_transactions(
+            page_signatures,
+            Some(GetTransactionConfig {
+                max_supported_transaction_version: Some(0),
+                commitment: None,
+                encoding: None,
+            }),
+        )
+        .await?;
 
-    for batch in batches {
-        let transactions: Vec<crate::client::GetTransactionResponse> = client
-            .get_transactions(
-                &batch,
-                Some(GetTransactionConfig {
-                    max_supported_transaction_version: Some(0),
-                    commitment: None,
-                    encoding: None,
-                }),
-            )
-            .await?;
+    let mut txs: Vec<Transaction> = Vec::new();
 
-        txs.extend(transactions.into_iter().filter_map(|tx| tx.result));
+    for tx in transactions {
+        match tx {
+            Ok(tx) => txs.extend(tx.result),
+            // Entries the node can't serve or that don't parse are skipped, the rest of the
+            // history is still usable. Only a failing connection aborts
+            Err(e) if e.is_transport_error() => return Err(e),
+            Err(_) => continue,
+        }
     }
 
     let mut parsed_transactions: Vec<ParsedTransaction> = txs
         .iter()
-        .map(|tx| parse_transaction(tx, &address))
+        .map(|tx| parse_transaction(tx, address))
         .filter_map(|x| x.ok())
         .collect::<Vec<ParsedTransaction>>();
 
@@ -175,9 +182,7 @@ fn parse_transaction(tx: &Transaction, signer: &Pubkey) -> Result<ParsedTransact
         .collect();
 
     for pre_balance in pre_token_balances {
-        let balance_change = balances
-            .entry(pre_balance.mint)
-            .or_insert(BalanceChange::default());
+        let balance_change = balances.entry(pre_balance.mint).or_default();
 
         balance_change.pre = FormattedAmount {
             amount: pre_balance.ui_token_amount.amount,
@@ -186,9 +191,7 @@ fn parse_transaction(tx: &Transaction, signer: &Pubkey) -> Result<ParsedTransact
     }
 
     for post_balance in post_token_balances {
-        let balance_change = balances
-            .entry(post_balance.mint)
-            .or_insert(BalanceChange::default());
+        let balance_change = balances.entry(post_balance.mint).or_default();
 
         balance_change.post = FormattedAmount {
             amount: post_balance.ui_token_amount.amount,